@@ -0,0 +1,31 @@
+use eframe::egui;
+use egui_xml::load_layout_file;
+
+fn main() -> Result<(), eframe::Error> {
+    let options = eframe::NativeOptions {
+        ..Default::default()
+    };
+    eframe::run_native(
+        "My egui App",
+        options,
+        Box::new(|_cc| Ok(Box::<MyApp>::default())),
+    )
+}
+
+struct MyApp {
+    stroke_width: f32,
+}
+
+impl Default for MyApp {
+    fn default() -> Self {
+        Self { stroke_width: 2.0 }
+    }
+}
+
+impl eframe::App for MyApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            load_layout_file!("tests/border.xml");
+        });
+    }
+}
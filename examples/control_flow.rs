@@ -0,0 +1,114 @@
+use eframe::egui;
+use egui::{CornerRadius, Ui};
+use egui_xml::load_layout;
+
+fn main() -> Result<(), eframe::Error> {
+    let options = eframe::NativeOptions {
+        ..Default::default()
+    };
+    eframe::run_native(
+        "My egui App",
+        options,
+        Box::new(|_cc| Ok(Box::<MyApp>::default())),
+    )
+}
+
+struct Column {
+    name: String,
+    width: f32,
+    color: egui::Color32,
+}
+
+#[derive(PartialEq)]
+enum Summary {
+    Hidden,
+    Widths,
+    Names,
+}
+
+struct MyApp {
+    columns: Vec<Column>,
+    summary: Summary,
+}
+
+impl Default for MyApp {
+    fn default() -> Self {
+        Self {
+            columns: vec![
+                Column {
+                    name: "Yellow".to_string(),
+                    width: 150.0,
+                    color: egui::Color32::from_rgb(255, 255, 0),
+                },
+                Column {
+                    name: "Cyan".to_string(),
+                    width: 100.0,
+                    color: egui::Color32::from_rgb(0, 255, 255),
+                },
+                Column {
+                    name: "Magenta".to_string(),
+                    width: 200.0,
+                    color: egui::Color32::from_rgb(255, 0, 255),
+                },
+            ],
+            summary: Summary::Widths,
+        }
+    }
+}
+
+fn color_background(ui: &mut Ui, color: egui::Color32) {
+    ui.painter().rect_filled(
+        ui.available_rect_before_wrap(),
+        CornerRadius::same(5),
+        color,
+    );
+}
+
+impl eframe::App for MyApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            load_layout!(
+                <Strip direction="west" gap="4.0">
+                    <Strip direction="west" gap="4.0" size="exact" value="@self.columns.iter().map(|column| column.width + 4.0).sum::<f32>()">
+                        <For each="@self.columns.iter_mut()" as="column">
+                            <Panel size="exact" value="@column.width">
+                                color_background(ui, column.color);
+                                ui.text_edit_singleline(&mut column.name);
+                                ui.add(egui::Slider::new(&mut column.width, 50.0..=300.0));
+                            </Panel>
+                        </For>
+                    </Strip>
+                    <If cond="@self.summary != Summary::Hidden">
+                        <Match value="@self.summary" size="remainder">
+                            <Case pattern="Summary::Widths">
+                                <For each="@self.columns.iter()" as="column">
+                                    ui.label(format!("{}: {:.0}px", column.name, column.width));
+                                </For>
+                            </Case>
+                            <Case pattern="Summary::Names | Summary::Hidden">
+                                <For each="@self.columns.iter()" as="column">
+                                    ui.label(&column.name);
+                                </For>
+                            </Case>
+                        </Match>
+                    </If>
+                    <Panel size="exact" value="120.0">
+                        ui.radio_value(&mut self.summary, Summary::Hidden, "Hidden");
+                        ui.radio_value(&mut self.summary, Summary::Widths, "Widths");
+                        ui.radio_value(&mut self.summary, Summary::Names, "Names");
+
+                        <If cond="@self.columns.is_empty()">
+                            ui.label("No columns");
+                        </If>
+                        <ElseIf cond="@self.columns.len() == 1">
+                            ui.label("One column");
+                        </ElseIf>
+                        <Else>
+                            ui.label(format!("{} columns", self.columns.len()));
+                        </Else>
+                    </Panel>
+                </Strip>
+            );
+        });
+    }
+}
@@ -0,0 +1,68 @@
+use eframe::egui;
+use egui_xml::{load_layout, SortState};
+
+fn main() -> Result<(), eframe::Error> {
+    let options = eframe::NativeOptions {
+        ..Default::default()
+    };
+    eframe::run_native(
+        "My egui App",
+        options,
+        Box::new(|_cc| Ok(Box::<MyApp>::default())),
+    )
+}
+
+struct Customer {
+    name: String,
+}
+
+struct Order {
+    id: u32,
+    customer: Customer,
+    price: f32,
+    quantity: u32,
+}
+
+struct MyApp {
+    orders: Vec<Order>,
+    sort: SortState,
+}
+
+impl Default for MyApp {
+    fn default() -> Self {
+        let names = ["Ada", "Charles", "Grace", "Alan", "Edsger"];
+
+        Self {
+            orders: (0..1000)
+                .map(|index| Order {
+                    id: 1000 + index,
+                    customer: Customer {
+                        name: names[index as usize % names.len()].to_string(),
+                    },
+                    price: (index * 37 % 101) as f32 + 0.99,
+                    quantity: index % 7 + 1,
+                })
+                .collect(),
+            sort: SortState::default(),
+        }
+    }
+}
+
+impl eframe::App for MyApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            load_layout!(
+                <DataGrid rows="@self.orders" sort="@self.sort" as="order" striped="true" resizable="true">
+                    <Column header="Id" field="id" sortable="true" size="exact" value="60"/>
+                    <Column header="Customer" field="customer.name" sortable="true" size="relative" value="0.4"/>
+                    <Column header="Price" field="price" sortable="true">
+                        ui.label(format!("{:.2} €", order.price));
+                    </Column>
+                    <Column header="Total" field="@order.price * order.quantity as f32" sortable="true" size="remainder">
+                        ui.label(format!("{:.2} €", order.price * order.quantity as f32));
+                    </Column>
+                </DataGrid>
+            );
+        });
+    }
+}
@@ -17,7 +17,7 @@ struct MyApp;
 fn color_background(ui: &mut egui::Ui, color: egui::Color32) {
     ui.painter().rect_filled(
         ui.available_rect_before_wrap(),
-        egui::Rounding::same(5.0),
+        egui::CornerRadius::same(5),
         color,
     );
 }
@@ -25,8 +25,6 @@ fn color_background(ui: &mut egui::Ui, color: egui::Color32) {
 impl eframe::App for MyApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         egui::CentralPanel::default().show(ctx, |ui| {
-            let vertical_gap_symetric = 1.5;
-
             load_layout_file!("tests/strip.xml");
         });
     }
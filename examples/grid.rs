@@ -0,0 +1,71 @@
+use eframe::egui;
+use egui_xml::load_layout;
+
+fn main() -> Result<(), eframe::Error> {
+    let options = eframe::NativeOptions {
+        ..Default::default()
+    };
+    eframe::run_native(
+        "My egui App",
+        options,
+        Box::new(|_cc| Ok(Box::<MyApp>::default())),
+    )
+}
+
+struct Property {
+    name: &'static str,
+    value: f32,
+}
+
+struct MyApp {
+    striped: bool,
+    properties: Vec<Property>,
+}
+
+impl Default for MyApp {
+    fn default() -> Self {
+        Self {
+            striped: true,
+            properties: vec![
+                Property {
+                    name: "Width",
+                    value: 250.0,
+                },
+                Property {
+                    name: "Height",
+                    value: 120.0,
+                },
+                Property {
+                    name: "Gap",
+                    value: 4.0,
+                },
+            ],
+        }
+    }
+}
+
+impl eframe::App for MyApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            load_layout!(
+                <Strip direction="north">
+                    <Panel size="exact" value="30.0">
+                        ui.checkbox(&mut self.striped, "Striped");
+                    </Panel>
+                    <Grid id="properties" columns="2" striped="@self.striped" spacing="8,4" min_col_width="80" size="remainder">
+                        <Row>
+                            ui.strong("Property");
+                            ui.strong("Value");
+                        </Row>
+                        <For each="@self.properties.iter_mut()" as="property">
+                            <Row>
+                                ui.label(property.name);
+                                ui.add(egui::DragValue::new(&mut property.value));
+                            </Row>
+                        </For>
+                    </Grid>
+                </Strip>
+            );
+        });
+    }
+}
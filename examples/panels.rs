@@ -0,0 +1,26 @@
+use eframe::egui;
+use egui_xml::load_layout_file;
+
+fn main() -> Result<(), eframe::Error> {
+    let options = eframe::NativeOptions {
+        ..Default::default()
+    };
+    eframe::run_native(
+        "My egui App",
+        options,
+        Box::new(|_cc| Ok(Box::<MyApp>::default())),
+    )
+}
+
+#[derive(Default)]
+struct MyApp {
+    show_nav: bool,
+    selected: usize,
+    clicks: u32,
+}
+
+impl eframe::App for MyApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        load_layout_file!("tests/panels.xml");
+    }
+}
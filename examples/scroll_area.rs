@@ -0,0 +1,67 @@
+use eframe::egui;
+use egui_xml::load_layout;
+
+fn main() -> Result<(), eframe::Error> {
+    let options = eframe::NativeOptions {
+        ..Default::default()
+    };
+    eframe::run_native(
+        "My egui App",
+        options,
+        Box::new(|_cc| Ok(Box::<MyApp>::default())),
+    )
+}
+
+struct MyApp {
+    log: Vec<String>,
+    follow: bool,
+    offset: egui::Vec2,
+}
+
+impl Default for MyApp {
+    fn default() -> Self {
+        Self {
+            log: (1..=50).map(|line| format!("line {}", line)).collect(),
+            follow: true,
+            offset: egui::Vec2::ZERO,
+        }
+    }
+}
+
+impl eframe::App for MyApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            load_layout!(
+                <Strip direction="north">
+                    <Panel size="exact" value="30.0">
+                        ui.horizontal(|ui| {
+                            if ui.button("Add line").clicked() {
+                                self.log.push(format!("line {}", self.log.len() + 1));
+                            }
+
+                            ui.checkbox(&mut self.follow, "Follow");
+
+                            if ui.button("Top").clicked() {
+                                self.offset = egui::Vec2::ZERO;
+                            }
+                        });
+                    </Panel>
+                    <ScrollArea id="log" direction="vertical" auto_shrink="false" stick_to_bottom="@self.follow" offset="@self.offset" size="relative" value="0.5">
+                        <For each="@self.log.iter()" as="line">
+                            ui.monospace(line);
+                        </For>
+                    </ScrollArea>
+                    <ScrollArea id="wide" direction="both" max_height="120" size="remainder">
+                        <For each="@0..20" as="row">
+                            ui.horizontal(|ui| {
+                                for column in 0..30 {
+                                    ui.label(format!("{}:{}", row, column));
+                                }
+                            });
+                        </For>
+                    </ScrollArea>
+                </Strip>
+            );
+        });
+    }
+}
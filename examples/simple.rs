@@ -1,5 +1,5 @@
 use eframe::egui;
-use egui::{Rounding, Ui};
+use egui::{CornerRadius, Ui};
 use egui_xml::load_layout;
 
 fn main() -> Result<(), eframe::Error> {
@@ -16,8 +16,11 @@ fn main() -> Result<(), eframe::Error> {
 struct MyApp;
 
 fn color_background(ui: &mut Ui, color: egui::Color32) {
-    ui.painter()
-        .rect_filled(ui.available_rect_before_wrap(), Rounding::same(5.0), color);
+    ui.painter().rect_filled(
+        ui.available_rect_before_wrap(),
+        CornerRadius::same(5),
+        color,
+    );
 }
 
 impl eframe::App for MyApp {
@@ -0,0 +1,40 @@
+use eframe::egui;
+use egui_xml::load_layout_file;
+
+fn main() -> Result<(), eframe::Error> {
+    let options = eframe::NativeOptions {
+        ..Default::default()
+    };
+    eframe::run_native(
+        "My egui App",
+        options,
+        Box::new(|_cc| Ok(Box::<MyApp>::default())),
+    )
+}
+
+#[derive(Default)]
+struct MyApp {
+    selected: usize,
+}
+
+impl MyApp {
+    fn sidebar(&mut self, ui: &mut egui::Ui) {
+        for index in 0..5 {
+            ui.selectable_value(&mut self.selected, index, format!("Item {}", index));
+        }
+    }
+}
+
+impl eframe::App for MyApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            load_layout_file!(
+                "tests/slots.xml",
+                sidebar => self.sidebar(ui),
+                content => {
+                    ui.label(format!("Item {} is selected", self.selected));
+                },
+            );
+        });
+    }
+}
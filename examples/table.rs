@@ -0,0 +1,82 @@
+use eframe::egui;
+use egui_xml::load_layout;
+
+fn main() -> Result<(), eframe::Error> {
+    let options = eframe::NativeOptions {
+        ..Default::default()
+    };
+    eframe::run_native(
+        "My egui App",
+        options,
+        Box::new(|_cc| Ok(Box::<MyApp>::default())),
+    )
+}
+
+struct Order {
+    id: u32,
+    customer: &'static str,
+    total: f32,
+}
+
+struct MyApp {
+    striped: bool,
+    orders: Vec<Order>,
+}
+
+impl Default for MyApp {
+    fn default() -> Self {
+        Self {
+            striped: true,
+            orders: vec![
+                Order {
+                    id: 1001,
+                    customer: "Ada Lovelace",
+                    total: 42.5,
+                },
+                Order {
+                    id: 1002,
+                    customer: "Charles Babbage",
+                    total: 17.0,
+                },
+                Order {
+                    id: 1003,
+                    customer: "Grace Hopper",
+                    total: 108.25,
+                },
+            ],
+        }
+    }
+}
+
+impl eframe::App for MyApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            load_layout!(
+                <Strip direction="north">
+                    <Panel size="exact" value="30.0">
+                        ui.checkbox(&mut self.striped, "Striped");
+                    </Panel>
+                    <Table id="orders" striped="@self.striped" resizable="true" size="remainder">
+                        <Column size="exact" value="60"/>
+                        <Column size="relative" value="0.5" min="100" clip="true"/>
+                        <Column size="remainder"/>
+                        <Header height="20">
+                            <Cell>ui.strong("Id");</Cell>
+                            <Cell>ui.strong("Customer");</Cell>
+                            <Cell>ui.strong("Total");</Cell>
+                        </Header>
+                        <Body row_height="18">
+                            <For each="@self.orders.iter()" as="order">
+                                <Row>
+                                    <Cell>ui.label(order.id.to_string());</Cell>
+                                    <Cell>ui.label(order.customer);</Cell>
+                                    <Cell>ui.label(format!("{:.2}", order.total));</Cell>
+                                </Row>
+                            </For>
+                        </Body>
+                    </Table>
+                </Strip>
+            );
+        });
+    }
+}
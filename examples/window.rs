@@ -0,0 +1,48 @@
+use eframe::egui;
+use egui_xml::load_layout;
+
+fn main() -> Result<(), eframe::Error> {
+    let options = eframe::NativeOptions {
+        ..Default::default()
+    };
+    eframe::run_native(
+        "My egui App",
+        options,
+        Box::new(|_cc| Ok(Box::<MyApp>::default())),
+    )
+}
+
+struct MyApp {
+    settings_open: bool,
+    name: String,
+    volume: f32,
+}
+
+impl Default for MyApp {
+    fn default() -> Self {
+        Self {
+            settings_open: true,
+            name: "egui_xml".to_string(),
+            volume: 0.5,
+        }
+    }
+}
+
+impl eframe::App for MyApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading(format!("Hello {}!", self.name));
+            ui.toggle_value(&mut self.settings_open, "Settings");
+        });
+
+        load_layout!(
+            <Window title="Settings" open="@self.settings_open" resizable="true" default_size="300,150" anchor="top-right" anchor_offset="-10,10">
+                ui.text_edit_singleline(&mut self.name);
+                ui.add(egui::Slider::new(&mut self.volume, 0.0..=1.0).text("Volume"));
+            </Window>
+            <Window title="About" collapsible="false" anchor="bottom-left">
+                ui.label("Declared with load_layout!");
+            </Window>
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use std::path::{Path, PathBuf};
+
+/// The places a layout path is looked up, in order.
+///
+/// Like `include_str!` a relative path is first taken relative to the source file invoking the
+/// macro, then relative to the `CARGO_MANIFEST_DIR` of the crate being built.
+pub fn candidates(path: &str) -> Vec<PathBuf> {
+    let path = Path::new(path);
+
+    if path.is_absolute() {
+        return vec![path.to_path_buf()];
+    }
+
+    let mut candidates = Vec::new();
+
+    // outside of a macro expansion, e.g. in unit tests, there is no invoking file
+    let invoking_dir = proc_macro::is_available()
+        .then(|| proc_macro::Span::call_site().local_file())
+        .flatten()
+        .and_then(|file| file.parent().map(Path::to_path_buf));
+
+    if let Some(dir) = invoking_dir {
+        candidates.push(dir.join(path));
+    }
+
+    if let Some(dir) = std::env::var_os("CARGO_MANIFEST_DIR") {
+        candidates.push(PathBuf::from(dir).join(path));
+    }
+
+    candidates
+}
+
+/// The first candidate of `path` that exists, or all candidates that were tried.
+///
+/// Paths in a layout file are relative to `dir`, the directory of that file.
+pub fn resolve(path: &str, dir: Option<&Path>) -> Result<PathBuf, Vec<PathBuf>> {
+    let candidates = match dir {
+        Some(dir) => vec![dir.join(path)],
+        None => candidates(path),
+    };
+
+    match candidates.iter().find(|candidate| candidate.is_file()) {
+        Some(found) => Ok(found.canonicalize().unwrap_or_else(|_| found.clone())),
+        None => Err(candidates),
+    }
+}
@@ -0,0 +1,173 @@
+use egui_xml_parser::{
+    attribute::parse_string,
+    visit::{visit_children_mut, VisitMut},
+    Node, NodeId, ParseError, XMLForm,
+};
+
+use crate::{file, source::LayoutFile};
+
+/// Replaces every `<Include src="..."/>` by the layout of the file it names.
+///
+/// Included files are added to `files`, their nodes are located in them.
+pub fn expand_includes(form: &mut XMLForm, files: &mut Vec<LayoutFile>) -> Result<(), ParseError> {
+    let mut includes = Includes {
+        files,
+        chain: vec![0],
+        error: None,
+    };
+
+    let root = form.root();
+    includes.visit_node_mut(form, root);
+
+    match includes.error {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+struct Includes<'f> {
+    files: &'f mut Vec<LayoutFile>,
+    /// The files currently being included, outermost first.
+    chain: Vec<usize>,
+    error: Option<ParseError>,
+}
+
+impl VisitMut for Includes<'_> {
+    fn visit_element_mut(&mut self, form: &mut XMLForm, id: NodeId) {
+        if self.error.is_some() {
+            return;
+        }
+
+        if form.node(id).name() != "Include" {
+            visit_children_mut(self, form, id);
+            return;
+        }
+
+        match self.include(form, id) {
+            // the fragment had its own includes replaced already
+            Ok(fragment) if self.error.is_none() => {
+                form.splice(id, &fragment);
+            }
+            Ok(_) => (),
+            Err(error) => self.error = Some(error),
+        }
+    }
+}
+
+impl Includes<'_> {
+    fn include(&mut self, form: &XMLForm, id: NodeId) -> Result<XMLForm, ParseError> {
+        let attributes = form
+            .node(id)
+            .get_attributes()
+            .expect("elements have attributes");
+
+        attributes.check(|name| name == "src")?;
+
+        // the element is replaced as a whole, content inside it would be lost
+        if let Some(&child) = form.children(id).first() {
+            return Err(match form.node(child) {
+                Node::Rust { location, .. } => ParseError::UnexpectedRust {
+                    element: "Include".to_string(),
+                    location: *location,
+                },
+                node => ParseError::UnexpectedElement {
+                    element: node.name().to_string(),
+                    parent: "Include".to_string(),
+                    location: node.location(),
+                },
+            });
+        }
+
+        let src = parse_string(attributes, "src")?;
+        let location = attributes.location();
+
+        let including = &self.files[location.file];
+        let dir = including.path.as_ref().and_then(|path| path.parent());
+
+        let path = file::resolve(&src, dir).map_err(|tried| ParseError::IncludeNotFound {
+            src: src.clone(),
+            tried: tried
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect(),
+            location,
+        })?;
+
+        let cycle_start = self
+            .chain
+            .iter()
+            .position(|&file| self.files[file].path.as_ref() == Some(&path));
+
+        if let Some(start) = cycle_start {
+            let mut chain: Vec<String> = self.chain[start..]
+                .iter()
+                .map(|&file| self.files[file].name.clone())
+                .collect();
+            chain.push(src);
+
+            return Err(ParseError::IncludeCycle { chain, location });
+        }
+
+        let text = std::fs::read_to_string(&path).map_err(|e| ParseError::IncludeNotFound {
+            src: src.clone(),
+            tried: vec![format!("{}: {}", path.display(), e)],
+            location,
+        })?;
+
+        let file = self.files.len();
+
+        self.files.push(LayoutFile {
+            name: src,
+            path: Some(path),
+            included_at: Some(location),
+        });
+
+        let mut fragment = XMLForm::try_from(text)
+            .map_err(|e| e.in_file(file))?
+            .in_file(file);
+
+        self.chain.push(file);
+
+        let root = fragment.root();
+        self.visit_node_mut(&mut fragment, root);
+
+        self.chain.pop();
+
+        Ok(fragment)
+    }
+}
+
+mod test {
+    #[test]
+    fn test_include_errors() {
+        use crate::{compile_error, file_layout};
+
+        let error = |path: &str| {
+            compile_error(&file_layout(format!("{:?}", path).parse().unwrap()))
+                .map(|(message, _, _)| message)
+        };
+
+        assert_eq!(
+            error("tests/fixtures/cycle_a.xml"),
+            Some(
+                "cycle_b.xml:2:5: include cycle: tests/fixtures/cycle_a.xml -> cycle_b.xml -> cycle_a.xml"
+                    .to_string()
+            )
+        );
+
+        let not_found = error("tests/fixtures/include_missing.xml").unwrap();
+
+        assert!(not_found.starts_with(
+            "tests/fixtures/include_missing.xml:2:5: included file missing.xml not found, tried:"
+        ));
+        assert!(not_found.ends_with("missing.xml"));
+
+        assert_eq!(
+            error("tests/fixtures/include_children.xml"),
+            Some(
+                "tests/fixtures/include_children.xml:3:9: <Panel> is not allowed inside <Include>"
+                    .to_string()
+            )
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use egui_xml_parser::{element::Border, NodeId, ParseError};
+use proc_macro2::Span;
+use quote::{quote, TokenStreamExt};
+
+use crate::XMLContext;
+
+pub fn expand_border(
+    border: NodeId,
+    info: &Border,
+    ctx: &XMLContext,
+) -> Result<proc_macro2::TokenStream, ParseError> {
+    let mut frame = quote! { egui::Frame::new() };
+
+    // a stroke needs both, whichever is missing comes from the style
+    if info.stroke.is_some() || info.color.is_some() {
+        let width = match info.stroke.clone() {
+            Some(stroke) => ctx.attribute(border, "stroke", stroke.into()),
+            None => quote! { 1.0 },
+        };
+
+        let color = match info.color.clone() {
+            Some(color) => ctx.attribute(border, "color", color.into()),
+            None => quote! { ui.visuals().widgets.noninteractive.bg_stroke.color },
+        };
+
+        frame.append_all(quote! { .stroke(egui::Stroke::new(#width, #color)) });
+    }
+
+    let modifiers = [
+        (
+            "rounding",
+            "corner_radius",
+            info.rounding.clone().map(Into::into),
+        ),
+        (
+            "inner_margin",
+            "inner_margin",
+            info.inner_margin.clone().map(Into::into),
+        ),
+        (
+            "outer_margin",
+            "outer_margin",
+            info.outer_margin.clone().map(Into::into),
+        ),
+        ("fill", "fill", info.fill.clone().map(Into::into)),
+    ];
+
+    for (attribute, method, value) in modifiers {
+        if let Some(value) = value {
+            let stream = ctx.attribute(border, attribute, value);
+            let method = proc_macro2::Ident::new(method, Span::call_site());
+
+            frame.append_all(quote! { .#method(#stream) });
+        }
+    }
+
+    let body = crate::expand_nodes(ctx.layout.children(border), ctx)?;
+
+    Ok(quote! {
+        #frame.show(ui, |ui| {
+            #body
+        });
+    })
+}
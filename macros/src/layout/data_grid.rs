@@ -0,0 +1,252 @@
+use egui_xml_parser::{
+    attribute::HybridAttribute,
+    element::{DataColumn, DataGrid, Size},
+    Element, NodeId, ParseError,
+};
+use quote::{quote, TokenStreamExt};
+
+use super::table::{expand_column, table_builder, table_id};
+use crate::XMLContext;
+
+pub fn expand_data_grid(
+    grid: NodeId,
+    info: &DataGrid,
+    ctx: &XMLContext,
+) -> Result<proc_macro2::TokenStream, ParseError> {
+    let columns: Vec<(NodeId, &DataColumn)> = ctx
+        .layout
+        .children(grid)
+        .iter()
+        .filter_map(|&child| match ctx.layout.element(child) {
+            Element::DataColumn(column) => Some((child, column)),
+            _ => None,
+        })
+        .collect();
+
+    let binding = info.binding.clone().unwrap_or_else(|| quote! { row });
+    let rows = ctx.attribute(grid, "rows", info.rows.clone());
+
+    let id = table_id(grid, &info.table, ctx);
+    let mut builder = table_builder(grid, &info.table, ctx);
+
+    let mut headers = quote! {};
+    let mut cells = quote! {};
+    let mut sorts = quote! {};
+
+    for (index, &(column, column_info)) in columns.iter().enumerate() {
+        let width = expand_column(column, &column_info.column, ctx);
+
+        builder.append_all(quote! { .column(#width) });
+
+        // the value of the field for the row bound to `binding`
+        let value = column_info.field.clone().map(|field| match field {
+            HybridAttribute::Literal(path) => {
+                let path = ctx.attribute(column, "field", path.into());
+
+                quote! { #binding #path }
+            }
+            HybridAttribute::DynamicRust(stream) => ctx.attribute(column, "field", stream),
+        });
+
+        let header = match column_info.header.clone() {
+            Some(header) => ctx.attribute(column, "header", header),
+            None => quote! { "" },
+        };
+
+        // a dynamic `sortable` is checked when the header is shown and when sorting
+        let sortable = match column_info.sortable.clone() {
+            Some(HybridAttribute::DynamicRust(stream)) => {
+                Some(ctx.attribute(column, "sortable", stream))
+            }
+            _ if column_info.maybe_sortable() => Some(quote! { true }),
+            _ => None,
+        };
+
+        let plain_header = quote! {
+            macro_row.col(|ui| {
+                ui.strong(#header);
+            });
+        };
+
+        if let Some(sortable) = sortable {
+            headers.append_all(quote! {
+                if #sortable {
+                    macro_row.col(|ui| {
+                        let macro_sorted = macro_sort.column == Some(#index);
+                        let macro_arrow = match (macro_sorted, macro_sort.descending) {
+                            (false, _) => "",
+                            (true, false) => " ⏶",
+                            (true, true) => " ⏷",
+                        };
+
+                        let macro_text = egui::RichText::new(format!("{}{}", #header, macro_arrow));
+
+                        if ui.selectable_label(macro_sorted, macro_text.strong()).clicked() {
+                            macro_sort.toggle(#index);
+                        }
+                    });
+                } else {
+                    #plain_header
+                }
+            });
+
+            sorts.append_all(quote! {
+                Some(#index) if #sortable => macro_order.sort_by(|&macro_a, &macro_b| {
+                    let #binding = &macro_rows[macro_a];
+                    let macro_a = &(#value);
+                    let #binding = &macro_rows[macro_b];
+                    let macro_b = &(#value);
+
+                    macro_a
+                        .partial_cmp(macro_b)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                }),
+            });
+        } else {
+            headers.append_all(plain_header);
+        }
+
+        let content = match ctx.layout.children(column) {
+            [] => quote! { ui.label((#value).to_string()); },
+            children => crate::expand_nodes(children, ctx)?,
+        };
+
+        cells.append_all(quote! {
+            macro_row.col(|ui| {
+                #content
+            });
+        });
+    }
+
+    let relative = columns
+        .iter()
+        .any(|(_, column)| matches!(column.column.size, Some(Size::Relative { .. })));
+
+    // the builder borrows `ui`, everything taken from it is read before
+    let width = relative.then(|| quote! { let macro_table_width = ui.available_width(); });
+
+    let row_height = match info.row_height.clone() {
+        Some(height) => ctx.attribute(grid, "row_height", height.into()),
+        None => quote! { ui.spacing().interact_size.y },
+    };
+
+    let sortable = columns.iter().any(|(_, column)| column.maybe_sortable());
+
+    // a bound state is changed in place, otherwise it lives in the memory of `ui`
+    let (load, store) = match (sortable, info.sort.clone()) {
+        (false, _) => (quote! {}, quote! {}),
+        (true, Some(sort)) => {
+            let sort = ctx.attribute(grid, "sort", sort);
+
+            (
+                quote! { let macro_sort: &mut egui_xml::SortState = &mut #sort; },
+                quote! {},
+            )
+        }
+        (true, None) => (
+            quote! {
+                let macro_sort_id = ui.id().with(macro_table_id);
+                let mut macro_sort_state = ui
+                    .data_mut(|data| data.get_temp::<egui_xml::SortState>(macro_sort_id))
+                    .unwrap_or_default();
+                let macro_sort = &mut macro_sort_state;
+            },
+            quote! {
+                ui.data_mut(|data| data.insert_temp(macro_sort_id, macro_sort_state));
+            },
+        ),
+    };
+
+    let sort_rows = quote! {
+        let mut macro_order: Vec<usize> = (0..macro_rows.len()).collect();
+
+        match macro_sort.column {
+            #sorts
+            _ => {}
+        }
+
+        if macro_sort.descending {
+            macro_order.reverse();
+        }
+    };
+
+    // with a `version` sorting is redone only when it, the sort state or the number of rows
+    // changes, otherwise every frame
+    let (order_id, order, index) = match (sortable, info.version.clone()) {
+        (false, _) => (quote! {}, quote! {}, quote! { macro_row.index() }),
+        (true, None) => (
+            quote! {},
+            sort_rows,
+            quote! { macro_order[macro_row.index()] },
+        ),
+        (true, Some(version)) => {
+            let version = ctx.attribute(grid, "version", version);
+
+            (
+                quote! {
+                    let macro_ctx = ui.ctx().clone();
+                    let macro_order_id = ui.id().with(macro_table_id).with("order");
+                    let macro_version = egui::Id::new(&#version);
+                },
+                quote! {
+                    let macro_order_key = (*macro_sort, macro_rows.len(), macro_version);
+                    let macro_cached = macro_ctx.data(|data| {
+                        data.get_temp::<(
+                            (egui_xml::SortState, usize, egui::Id),
+                            std::sync::Arc<Vec<usize>>,
+                        )>(macro_order_id)
+                    });
+
+                    let macro_order = match macro_cached {
+                        Some((macro_key, macro_order)) if macro_key == macro_order_key => {
+                            macro_order
+                        }
+                        _ => {
+                            #sort_rows
+
+                            let macro_order = std::sync::Arc::new(macro_order);
+
+                            macro_ctx.data_mut(|data| {
+                                data.insert_temp(
+                                    macro_order_id,
+                                    (macro_order_key, macro_order.clone()),
+                                )
+                            });
+
+                            macro_order
+                        }
+                    };
+                },
+                quote! { macro_order[macro_row.index()] },
+            )
+        }
+    };
+
+    Ok(quote! {
+        {
+            #id
+            let macro_rows: &[_] = &#rows;
+            let macro_row_height = #row_height;
+            #width
+            #load
+            #order_id
+
+            #builder
+                .header(macro_row_height, |mut macro_row| {
+                    #headers
+                })
+                .body(|macro_body| {
+                    #order
+
+                    macro_body.rows(macro_row_height, macro_rows.len(), |mut macro_row| {
+                        #[allow(unused_variables)]
+                        let #binding = &macro_rows[#index];
+
+                        #cells
+                    });
+                });
+
+            #store
+        }
+    })
+}
@@ -0,0 +1,128 @@
+use egui_xml_parser::{
+    element::{Case, For, If, Let, Match},
+    Element, NodeId, ParseError,
+};
+use quote::{quote, ToTokens};
+use syn::{parse::Parser, Pat};
+
+use crate::XMLContext;
+
+/// A `<For>` outside of a strip, its children become the body of a `for` loop.
+pub fn expand_for(
+    node: NodeId,
+    info: &For,
+    ctx: &XMLContext,
+) -> Result<proc_macro2::TokenStream, ParseError> {
+    let each = ctx.attribute(node, "each", info.each.clone());
+    let binding = ctx.attribute(node, "as", info.binding.clone());
+
+    let body = crate::expand_nodes(ctx.layout.children(node), ctx)?;
+
+    Ok(quote! {
+        for #binding in #each {
+            #body
+        }
+    })
+}
+
+/// An `<If>` or `<ElseIf>`, adjacent branches are generated one after the other so they form a
+/// single `if` / `else if` / `else` chain.
+pub fn expand_if(
+    node: NodeId,
+    info: &If,
+    ctx: &XMLContext,
+) -> Result<proc_macro2::TokenStream, ParseError> {
+    let cond = ctx.attribute(node, "cond", info.cond.clone());
+
+    let body = crate::expand_nodes(ctx.layout.children(node), ctx)?;
+
+    let keyword = match ctx.layout.element(node) {
+        Element::ElseIf(_) => quote! { else if },
+        _ => quote! { if },
+    };
+
+    Ok(quote! {
+        #keyword #cond {
+            #body
+        }
+    })
+}
+
+pub fn expand_else(node: NodeId, ctx: &XMLContext) -> Result<proc_macro2::TokenStream, ParseError> {
+    let body = crate::expand_nodes(ctx.layout.children(node), ctx)?;
+
+    Ok(quote! {
+        else {
+            #body
+        }
+    })
+}
+
+pub fn expand_match(
+    node: NodeId,
+    info: &Match,
+    ctx: &XMLContext,
+) -> Result<proc_macro2::TokenStream, ParseError> {
+    let value = ctx.attribute(node, "value", info.value.clone());
+
+    // validation leaves only <Case>s in a <Match>
+    let arms = crate::expand_nodes(ctx.layout.children(node), ctx)?;
+
+    Ok(quote! {
+        match #value {
+            #arms
+        }
+    })
+}
+
+/// A `<Case>`, the arm of the `<Match>` it is in.
+pub fn expand_case(
+    node: NodeId,
+    info: &Case,
+    ctx: &XMLContext,
+) -> Result<proc_macro2::TokenStream, ParseError> {
+    let attributes = ctx
+        .layout
+        .form()
+        .node(node)
+        .get_attributes()
+        .expect("elements have attributes");
+
+    let pattern = Pat::parse_multi_with_leading_vert
+        .parse2(info.pattern.clone())
+        .map_err(|_| ParseError::InvalidRust {
+            element: "Case".to_string(),
+            attribute: Some("pattern".to_string()),
+            code: attributes.get("pattern").unwrap_or_default().to_string(),
+            location: attributes.location_of("pattern"),
+        })?;
+
+    let pattern = ctx.attribute(node, "pattern", pattern.to_token_stream());
+
+    let body = crate::expand_nodes(ctx.layout.children(node), ctx)?;
+
+    Ok(quote! {
+        #pattern => {
+            #body
+        }
+    })
+}
+
+/// A `<Let>`, its children are generated in a block after the binding.
+pub fn expand_let(
+    node: NodeId,
+    info: &Let,
+    ctx: &XMLContext,
+) -> Result<proc_macro2::TokenStream, ParseError> {
+    let name = ctx.attribute(node, "name", info.name.clone());
+    let value = ctx.attribute(node, "value", info.value.clone());
+
+    let body = crate::expand_nodes(ctx.layout.children(node), ctx)?;
+
+    Ok(quote! {
+        {
+            let #name = #value;
+            #body
+        }
+    })
+}
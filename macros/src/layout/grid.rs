@@ -0,0 +1,69 @@
+use egui_xml_parser::{element::Grid, NodeId, ParseError};
+use proc_macro2::Span;
+use quote::{quote, TokenStreamExt};
+
+use crate::XMLContext;
+
+/// The id salt of an element without an `id`, unique among the widgets of its `ui`.
+///
+/// It follows the position in the `ui`, so the iterations of a `<For>`, the uses of a template
+/// and other layouts in the same `ui` get their own. It is stable across frames as long as the
+/// widgets before it are.
+pub fn auto_id(kind: &str) -> proc_macro2::TokenStream {
+    quote! { ui.next_auto_id().with(#kind) }
+}
+
+pub fn expand_grid(
+    grid: NodeId,
+    info: &Grid,
+    ctx: &XMLContext,
+) -> Result<proc_macro2::TokenStream, ParseError> {
+    let id = match info.id.clone() {
+        Some(id) => ctx.attribute(grid, "id", id),
+        None => auto_id("egui_xml_grid"),
+    };
+
+    let mut builder = quote! { egui::Grid::new(#id) };
+
+    let modifiers = [
+        (
+            "columns",
+            "num_columns",
+            info.columns.clone().map(Into::into),
+        ),
+        ("striped", "striped", info.striped.clone().map(Into::into)),
+        ("spacing", "spacing", info.spacing.clone().map(Into::into)),
+        (
+            "min_col_width",
+            "min_col_width",
+            info.min_col_width.clone().map(Into::into),
+        ),
+    ];
+
+    for (attribute, method, value) in modifiers {
+        if let Some(value) = value {
+            let stream = ctx.attribute(grid, attribute, value);
+            let method = proc_macro2::Ident::new(method, Span::call_site());
+
+            builder.append_all(quote! { .#method(#stream) });
+        }
+    }
+
+    let body = crate::expand_nodes(ctx.layout.children(grid), ctx)?;
+
+    Ok(quote! {
+        #builder.show(ui, |ui| {
+            #body
+        });
+    })
+}
+
+/// A `<Row>`, its children are the cells of one grid row.
+pub fn expand_row(row: NodeId, ctx: &XMLContext) -> Result<proc_macro2::TokenStream, ParseError> {
+    let body = crate::expand_nodes(ctx.layout.children(row), ctx)?;
+
+    Ok(quote! {
+        #body
+        ui.end_row();
+    })
+}
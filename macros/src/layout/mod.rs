@@ -1 +1,9 @@
+pub mod border;
+pub mod data_grid;
+pub mod flow;
+pub mod grid;
+pub mod panel;
+pub mod scroll_area;
 pub mod strip;
+pub mod table;
+pub mod window;
@@ -0,0 +1,89 @@
+use egui_xml_parser::{
+    element::{CentralPanel, EdgePanel, PanelSide},
+    NodeId, ParseError,
+};
+use proc_macro2::Span;
+use quote::{quote, TokenStreamExt};
+
+use crate::XMLContext;
+
+fn ctx_var(
+    panel: NodeId,
+    ctx_var: &Option<proc_macro2::TokenStream>,
+    ctx: &XMLContext,
+) -> proc_macro2::TokenStream {
+    match ctx_var.clone() {
+        Some(ctx_var) => ctx.attribute(panel, "ctx", ctx_var),
+        None => quote! { ctx },
+    }
+}
+
+pub fn expand_edge_panel(
+    panel: NodeId,
+    info: &EdgePanel,
+    ctx: &XMLContext,
+) -> Result<proc_macro2::TokenStream, ParseError> {
+    let id = match info.id.clone() {
+        Some(id) => ctx.attribute(panel, "id", id),
+        None => {
+            // egui keeps the size of a panel under its id, stable across frames, unique for
+            // every panel of the macro call and every call
+            let location = ctx.layout.form().node(panel).location();
+            let id = format!(
+                "egui_xml_panel_{}_{}_{}",
+                ctx.source.files[location.file].name, location.line, location.column
+            );
+
+            quote! { (file!(), line!(), column!(), #id) }
+        }
+    };
+
+    let mut builder = match info.side {
+        PanelSide::Left => quote! { egui::SidePanel::left(egui::Id::new(#id)) },
+        PanelSide::Right => quote! { egui::SidePanel::right(egui::Id::new(#id)) },
+        PanelSide::Top => quote! { egui::TopBottomPanel::top(egui::Id::new(#id)) },
+        PanelSide::Bottom => quote! { egui::TopBottomPanel::bottom(egui::Id::new(#id)) },
+    };
+
+    let [default_size, min_size, max_size] = EdgePanel::size_attributes(info.side);
+
+    let modifiers = [
+        ("resizable", info.resizable.clone().map(Into::into)),
+        (default_size, info.default_size.clone().map(Into::into)),
+        (min_size, info.min_size.clone().map(Into::into)),
+        (max_size, info.max_size.clone().map(Into::into)),
+    ];
+
+    for (attribute, value) in modifiers {
+        if let Some(value) = value {
+            let stream = ctx.attribute(panel, attribute, value);
+            let method = proc_macro2::Ident::new(attribute, Span::call_site());
+
+            builder.append_all(quote! { .#method(#stream) });
+        }
+    }
+
+    let ctx_var = ctx_var(panel, &info.ctx, ctx);
+    let body = crate::expand_nodes(ctx.layout.children(panel), ctx)?;
+
+    Ok(quote! {
+        #builder.show(#ctx_var, |ui| {
+            #body
+        });
+    })
+}
+
+pub fn expand_central_panel(
+    panel: NodeId,
+    info: &CentralPanel,
+    ctx: &XMLContext,
+) -> Result<proc_macro2::TokenStream, ParseError> {
+    let ctx_var = ctx_var(panel, &info.ctx, ctx);
+    let body = crate::expand_nodes(ctx.layout.children(panel), ctx)?;
+
+    Ok(quote! {
+        egui::CentralPanel::default().show(#ctx_var, |ui| {
+            #body
+        });
+    })
+}
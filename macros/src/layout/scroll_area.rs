@@ -0,0 +1,68 @@
+use egui_xml_parser::{
+    element::{ScrollArea, ScrollDirection},
+    NodeId, ParseError,
+};
+use proc_macro2::Span;
+use quote::{quote, TokenStreamExt};
+
+use crate::XMLContext;
+
+pub fn expand_scroll_area(
+    area: NodeId,
+    info: &ScrollArea,
+    ctx: &XMLContext,
+) -> Result<proc_macro2::TokenStream, ParseError> {
+    let mut builder = match info.direction {
+        ScrollDirection::Vertical => quote! { egui::ScrollArea::vertical() },
+        ScrollDirection::Horizontal => quote! { egui::ScrollArea::horizontal() },
+        ScrollDirection::Both => quote! { egui::ScrollArea::both() },
+    };
+
+    if let Some(id) = info.id.clone() {
+        let id = ctx.attribute(area, "id", id);
+
+        builder.append_all(quote! { .id_salt(#id) });
+    }
+
+    let modifiers = [
+        ("max_width", info.max_width.clone().map(Into::into)),
+        ("max_height", info.max_height.clone().map(Into::into)),
+        ("auto_shrink", info.auto_shrink.clone().map(Into::into)),
+        (
+            "stick_to_bottom",
+            info.stick_to_bottom.clone().map(Into::into),
+        ),
+    ];
+
+    for (attribute, value) in modifiers {
+        if let Some(value) = value {
+            let stream = ctx.attribute(area, attribute, value);
+            let method = proc_macro2::Ident::new(attribute, Span::call_site());
+
+            builder.append_all(quote! { .#method(#stream) });
+        }
+    }
+
+    let body = crate::expand_nodes(ctx.layout.children(area), ctx)?;
+
+    let Some(offset) = info.offset.clone() else {
+        return Ok(quote! {
+            #builder.show(ui, |ui| {
+                #body
+            });
+        });
+    };
+
+    // the bound offset is where the area starts, scrolling writes it back
+    let offset = ctx.attribute(area, "offset", offset);
+
+    Ok(quote! {
+        #offset = #builder
+            .scroll_offset(#offset)
+            .show(ui, |ui| {
+                #body
+            })
+            .state
+            .offset;
+    })
+}
@@ -1,343 +1,363 @@
-use std::{
-    cell::RefCell,
-    collections::HashMap,
-    rc::Rc,
-    str::{from_utf8, FromStr},
-};
-
 use egui_xml_parser::{
-    attribute::{parse_optional_hybrid_attribute, AttributeBool, AttributeF32, HybridAttribute},
-    Node,
+    attribute::{AttributeF32, HybridAttribute},
+    element::{Size, Strip},
+    Element, NodeId, ParseError,
 };
-use proc_macro2::Span;
-use quote::{quote, TokenStreamExt};
+use std::collections::HashMap;
 
-use strum_macros::EnumString;
+use proc_macro2::Span;
+use quote::{format_ident, quote, TokenStreamExt};
 
 use crate::XMLContext;
 
-use egui_xml_parser::attribute::{
-    parse_hybrid_attribute, parse_optional_rust_attribute, parse_string,
-};
+pub fn size_modifiers(
+    expanded: &mut proc_macro2::TokenStream,
+    node: NodeId,
+    min: &Option<HybridAttribute<AttributeF32>>,
+    max: &Option<HybridAttribute<AttributeF32>>,
+    ctx: &XMLContext,
+) {
+    let min_fn = proc_macro2::Ident::new("at_least", Span::call_site());
+    let max_fn = proc_macro2::Ident::new("at_most", Span::call_site());
 
-#[derive(PartialEq, Eq, EnumString)]
-enum DirectionBlueprint {
-    #[strum(
-        serialize = "BottomUp",
-        serialize = "bottomup",
-        serialize = "bu",
-        serialize = "south",
-        serialize = "s"
-    )]
-    BottomUp,
-    #[strum(
-        serialize = "LeftToRight",
-        serialize = "lefttoright",
-        serialize = "ltr",
-        serialize = "west",
-        serialize = "w"
-    )]
-    LeftToRight,
-    #[strum(
-        serialize = "RightToLeft",
-        serialize = "righttoleft",
-        serialize = "rtl",
-        serialize = "east",
-        serialize = "e"
-    )]
-    RightToLeft,
-    #[strum(
-        serialize = "TopDown",
-        serialize = "topdown",
-        serialize = "td",
-        serialize = "north",
-        serialize = "n"
-    )]
-    TopDown,
-}
+    if let Some(min) = min.clone() {
+        let stream = ctx.attribute(node, "min", min.into());
+
+        expanded.append_all(quote! {.#min_fn(#stream)});
+    }
 
-struct StripBlueprint {
-    direction: DirectionBlueprint,
+    if let Some(max) = max.clone() {
+        let stream = ctx.attribute(node, "max", max.into());
 
-    // Rust Token Stream
-    gap: Option<HybridAttribute<AttributeF32>>,
-    separator: HybridAttribute<AttributeBool>,
-    ui: proc_macro2::TokenStream,
+        expanded.append_all(quote! {.#max_fn(#stream)});
+    }
 }
 
-impl TryFrom<&HashMap<String, Vec<u8>>> for StripBlueprint {
-    type Error = String;
+/// `egui_extras::Size` expression for the cell size of `node`.
+pub fn expand_size(node: NodeId, size: &Size, ctx: &XMLContext) -> proc_macro2::TokenStream {
+    match size.clone() {
+        Size::Remainder { min, max } => {
+            let mut expanded = quote! { egui_extras::Size::remainder() };
+
+            size_modifiers(&mut expanded, node, &min, &max, ctx);
 
-    fn try_from(attributes: &HashMap<String, Vec<u8>>) -> Result<Self, Self::Error> {
-        let direction = DirectionBlueprint::from_str(&parse_string(attributes, "direction")?)
-            .map_err(|err| format!("StripInfo couldn't be parsed! {:?}", err))?;
+            expanded
+        }
+        Size::Exact { value, min, max } => {
+            let stream = ctx.attribute(node, "value", value.into());
 
-        let gap = parse_optional_hybrid_attribute::<AttributeF32>(attributes, "gap")?;
+            let mut expanded = quote! { egui_extras::Size::exact(#stream) };
 
-        let separator = parse_optional_hybrid_attribute::<AttributeBool>(attributes, "separator")?
-            .unwrap_or(HybridAttribute::Literal(AttributeBool(false)));
+            size_modifiers(&mut expanded, node, &min, &max, ctx);
 
-        let ui = parse_optional_rust_attribute(attributes, "ui")?.unwrap_or(quote! { ui });
+            expanded
+        }
+        Size::Relative { value, min, max } => {
+            let stream = ctx.attribute(node, "value", value.into());
 
-        Ok(StripBlueprint {
-            direction,
-            gap,
-            separator,
-            ui,
-        })
-    }
-}
+            let mut expanded = quote! { egui_extras::Size::relative(#stream) };
 
-#[derive(PartialEq, Eq, EnumString)]
-enum SizeType {
-    #[strum(serialize = "Remainder", serialize = "remainder", serialize = "rem")]
-    Remainder,
-    #[strum(serialize = "Exact", serialize = "exact", serialize = "ex")]
-    Exact,
-    #[strum(serialize = "Relative", serialize = "relative", serialize = "rel")]
-    Relative,
-}
+            size_modifiers(&mut expanded, node, &min, &max, ctx);
 
-pub enum SizeBlueprint {
-    Remainder {
-        min: Option<HybridAttribute<AttributeF32>>,
-        max: Option<HybridAttribute<AttributeF32>>,
-    },
-    Exact {
-        value: HybridAttribute<AttributeF32>,
-
-        min: Option<HybridAttribute<AttributeF32>>,
-        max: Option<HybridAttribute<AttributeF32>>,
-    },
-    Relative {
-        value: HybridAttribute<AttributeF32>,
-
-        min: Option<HybridAttribute<AttributeF32>>,
-        max: Option<HybridAttribute<AttributeF32>>,
-    },
+            expanded
+        }
+    }
 }
 
-impl TryFrom<&HashMap<String, Vec<u8>>> for SizeBlueprint {
-    type Error = String;
-
-    fn try_from(attributes: &HashMap<String, Vec<u8>>) -> Result<Self, Self::Error> {
-        let size = match attributes.get("size") {
-            Some(size) => from_utf8(size).unwrap(),
-            None => return Err("Size Attribute doesn't exist!".to_string()),
-        };
+/// Walks the cells of a strip, the direct children and those inside a `<For>`, `<If>` or `<Let>`.
+struct Cells<'c, 'a> {
+    ctx: &'c XMLContext<'a>,
+    reversed: bool,
+    /// Put between two cells, if the strip has a gap.
+    gap: Option<proc_macro2::TokenStream>,
+    /// With a `<For>` or `<If>` the number of cells is only known at runtime, gaps then go
+    /// before every cell but the first counted by `macro_strip_cells`.
+    dynamic: bool,
+    /// Locals holding the condition of an `<If>` or the items of a `<For>`, see [`Cells::hoist`].
+    locals: HashMap<NodeId, proc_macro2::Ident>,
+    /// The sizes borrow the items of a hoisted `<For>`, the content consumes them.
+    content: bool,
+}
 
-        let size_type = SizeType::from_str(size)
-            .map_err(|err| format!("StripInfo couldn't be parsed! {:?}", err))?;
+impl Cells<'_, '_> {
+    /// Evaluates the conditions and items of the strip once, before the sizes and the content
+    /// both need them.
+    ///
+    /// Below a `<For>` or `<Let>` they may use its bindings, so they stay where they are.
+    /// A condition or items below an `<If>` are only evaluated if it holds.
+    fn hoist(
+        &mut self,
+        parent: NodeId,
+        guard: Option<&proc_macro2::Ident>,
+    ) -> proc_macro2::TokenStream {
+        let mut hoisted = quote! {};
+
+        for &child in self.ctx.layout.children(parent) {
+            match self.ctx.layout.element(child) {
+                Element::For(info) => {
+                    let each = self.ctx.attribute(child, "each", info.each.clone());
+                    let local = format_ident!("macro_strip_items_{}", self.locals.len());
+
+                    let items = quote! { (#each).into_iter().collect::<Vec<_>>() };
+
+                    hoisted.append_all(match guard {
+                        Some(guard) => quote! {
+                            let mut #local = if #guard { #items } else { Vec::new() };
+                        },
+                        None => quote! { let mut #local = #items; },
+                    });
+
+                    self.locals.insert(child, local);
+                }
+                Element::If(info) => {
+                    let cond = self.ctx.attribute(child, "cond", info.cond.clone());
+                    let local = format_ident!("macro_strip_cond_{}", self.locals.len());
 
-        let min = parse_optional_hybrid_attribute::<AttributeF32>(attributes, "min")?;
+                    hoisted.append_all(match guard {
+                        Some(guard) => quote! { let #local = #guard && (#cond); },
+                        None => quote! { let #local = #cond; },
+                    });
 
-        let max = parse_optional_hybrid_attribute::<AttributeF32>(attributes, "max")?;
+                    self.locals.insert(child, local.clone());
 
-        return match size_type {
-            SizeType::Remainder => {
-                return Ok(SizeBlueprint::Remainder { min, max });
-            }
-            _ => {
-                let hybrid_attribute = parse_hybrid_attribute::<AttributeF32>(attributes, "value")?;
-
-                match size_type {
-                    SizeType::Exact => Ok(SizeBlueprint::Exact {
-                        value: hybrid_attribute,
-                        min,
-                        max,
-                    }),
-                    SizeType::Relative => Ok(SizeBlueprint::Relative {
-                        value: hybrid_attribute,
-                        min,
-                        max,
-                    }),
-                    _ => panic!("Why you here!"),
+                    hoisted.append_all(self.hoist(child, Some(&local)));
                 }
+                _ => (),
             }
-        };
+        }
+
+        hoisted
     }
-}
 
-impl Into<proc_macro2::TokenStream> for SizeBlueprint {
-    fn into(self) -> proc_macro2::TokenStream {
-        let min_fn = proc_macro2::Ident::new("at_least", Span::call_site());
-        let max_fn = proc_macro2::Ident::new("at_most", Span::call_site());
+    fn expand<F>(
+        &self,
+        parent: NodeId,
+        first: &mut bool,
+        cell: &mut F,
+    ) -> Result<proc_macro2::TokenStream, ParseError>
+    where
+        F: FnMut(NodeId) -> Result<proc_macro2::TokenStream, ParseError>,
+    {
+        let mut children = self.ctx.layout.children(parent).to_vec();
 
-        match self {
-            SizeBlueprint::Remainder { min, max } => {
-                let mut expanded = quote! { egui_extras::Size::remainder() };
+        if self.reversed {
+            children.reverse();
+        }
 
-                if let Some(min) = min {
-                    let stream: proc_macro2::TokenStream = min.into();
+        let mut expanded = quote! {};
 
-                    expanded.append_all(quote! {.#min_fn(#stream)});
-                }
+        for child in children {
+            let header = match self.ctx.layout.element(child) {
+                Element::For(info) => {
+                    let binding = self.ctx.attribute(child, "as", info.binding.clone());
 
-                if let Some(max) = max {
-                    let stream: proc_macro2::TokenStream = max.into();
+                    let items = match self.locals.get(&child) {
+                        Some(items) => quote! { #items },
+                        None => {
+                            let each = self.ctx.attribute(child, "each", info.each.clone());
 
-                    expanded.append_all(quote! {.#max_fn(#stream)});
+                            quote! { (#each).into_iter().collect::<Vec<_>>() }
+                        }
+                    };
+
+                    // both passes bind a reference to the item, the contents may change it
+                    let each = match self.content {
+                        true => quote! { #items.iter_mut() },
+                        false => quote! { #items.iter() },
+                    };
+
+                    let each = if self.reversed {
+                        quote! { #each.rev() }
+                    } else {
+                        each
+                    };
+
+                    // the sizes may not need the item
+                    Some((
+                        quote! {
+                            #[allow(unused_variables)]
+                            for #binding in #each
+                        },
+                        quote! {},
+                    ))
                 }
+                Element::If(info) => {
+                    let cond = match self.locals.get(&child) {
+                        Some(cond) => quote! { #cond },
+                        None => self.ctx.attribute(child, "cond", info.cond.clone()),
+                    };
 
-                quote! {
-                    macro_strip_builder = macro_strip_builder.size(#expanded);
+                    Some((quote! { if #cond }, quote! {}))
                 }
-            }
-            SizeBlueprint::Exact { value, min, max } => {
-                let stream: proc_macro2::TokenStream = value.into();
-
-                let mut expanded = quote! { egui_extras::Size::exact(#stream) };
-
-                if let Some(min) = min {
-                    let stream: proc_macro2::TokenStream = min.into();
-
-                    expanded.append_all(quote! {.#min_fn(#stream)});
+                Element::Let(info) => {
+                    let name = self.ctx.attribute(child, "name", info.name.clone());
+                    let value = self.ctx.attribute(child, "value", info.value.clone());
+
+                    Some((
+                        quote! {},
+                        quote! {
+                            #[allow(unused_variables)]
+                            let #name = #value;
+                        },
+                    ))
                 }
+                _ => None,
+            };
 
-                if let Some(max) = max {
-                    let stream: proc_macro2::TokenStream = max.into();
+            if let Some((header, binding)) = header {
+                let body = self.expand(child, first, cell)?;
 
-                    expanded.append_all(quote! {.#max_fn(#stream)});
-                }
+                expanded.append_all(quote! {
+                    #header {
+                        #binding
+                        #body
+                    }
+                });
 
-                quote! {
-                    macro_strip_builder = macro_strip_builder.size(#expanded);
-                }
+                continue;
             }
-            SizeBlueprint::Relative { value, min, max } => {
-                let stream: proc_macro2::TokenStream = value.into();
 
-                let mut expanded = quote! { egui_extras::Size::relative(#stream) };
+            match (&self.gap, self.dynamic) {
+                (Some(gap), true) => expanded.append_all(quote! {
+                    if macro_strip_cells > 0 {
+                        #gap
+                    }
 
-                if let Some(min) = min {
-                    let stream: proc_macro2::TokenStream = min.into();
+                    macro_strip_cells += 1;
+                }),
+                (Some(gap), false) if !*first => expanded.append_all(gap.clone()),
+                _ => (),
+            }
 
-                    expanded.append_all(quote! {.#min_fn(#stream)});
-                }
+            *first = false;
 
-                if let Some(max) = max {
-                    let stream: proc_macro2::TokenStream = max.into();
+            expanded.append_all(cell(child)?);
+        }
 
-                    expanded.append_all(quote! {.#max_fn(#stream)});
-                }
+        Ok(expanded)
+    }
 
-                quote! {
-                    macro_strip_builder = macro_strip_builder.size(#expanded);
-                }
-            }
+    /// Declares the counter the cells of a dynamic strip need.
+    fn counter(&self) -> proc_macro2::TokenStream {
+        if self.dynamic && self.gap.is_some() {
+            quote! { let mut macro_strip_cells = 0usize; }
+        } else {
+            quote! {}
         }
     }
 }
 
 pub fn expand_strip(
-    strip: &Rc<RefCell<Node>>,
+    strip: NodeId,
+    info: &Strip,
     ctx: &XMLContext,
-) -> Result<proc_macro2::TokenStream, String> {
-    let children_borrow = strip.borrow();
-    let children = children_borrow.get_children().unwrap();
-
-    let attributes_borrow = strip.borrow();
-    let attributes = attributes_borrow.get_attributes().unwrap();
+) -> Result<proc_macro2::TokenStream, ParseError> {
+    let ui_var = match info.ui.clone() {
+        Some(ui) => ctx.attribute(strip, "ui", ui),
+        None => quote! { ui },
+    };
 
-    let info: StripBlueprint = attributes.try_into()?;
+    let dynamic = ctx
+        .layout
+        .children(strip)
+        .iter()
+        .any(|&child| matches!(ctx.layout.element(child), Element::For(_) | Element::If(_)));
 
-    let ui_var = info.ui;
+    let gap = info.gap.clone().map(|gap| {
+        let gap_stream = ctx.attribute(strip, "gap", gap.into());
 
-    let mut expanded = quote! {
-        let mut macro_strip_builder = egui_extras::StripBuilder::new(#ui_var);
-    };
+        let gap_fn = proc_macro2::Ident::new("exact", Span::call_site());
 
-    let iter: Vec<&Rc<RefCell<Node>>> = if info.direction == DirectionBlueprint::BottomUp
-        || info.direction == DirectionBlueprint::RightToLeft
-    {
-        children.iter().rev().collect()
-    } else {
-        children.iter().collect()
+        quote! {
+            macro_strip_builder = macro_strip_builder.size(egui_extras::Size::#gap_fn(#gap_stream));
+        }
+    });
+
+    let mut cells = Cells {
+        ctx,
+        reversed: info.direction.is_reversed(),
+        gap,
+        dynamic,
+        locals: HashMap::new(),
+        content: false,
     };
 
-    for (index, child) in iter.iter().enumerate() {
-        let child_blueprint: SizeBlueprint = match child.borrow().get_attributes() {
-            Some(attributes) => attributes.try_into()?,
-            None => return Err("No Rust allowed here!".to_string()),
+    let hoisted = cells.hoist(strip, None);
+    let counter = cells.counter();
+    let sizes = cells.expand(strip, &mut true, &mut |child| {
+        // validation guarantees a size on every cell of a strip
+        let size_expanded = match ctx.layout.element(child).size() {
+            Some(size) => expand_size(child, size, ctx),
+            None => quote! { egui_extras::Size::remainder() },
         };
 
-        let size_expanded: proc_macro2::TokenStream = child_blueprint.into();
-
-        expanded.append_all(size_expanded);
-
-        if iter.len() - 1 != index {
-            if let Some(gap) = info.gap.clone() {
-                let gap_stream = match gap {
-                    HybridAttribute::Literal(value) => value.into(),
-                    HybridAttribute::DynamicRust(stream) => stream,
-                };
+        Ok(quote! {
+            macro_strip_builder = macro_strip_builder.size(#size_expanded);
+        })
+    })?;
 
-                let gap_fn = proc_macro2::Ident::new("exact", Span::call_site());
+    let direction_ident = if info.direction.is_horizontal() {
+        proc_macro2::Ident::new("horizontal", Span::call_site())
+    } else {
+        proc_macro2::Ident::new("vertical", Span::call_site())
+    };
 
-                expanded.append_all(quote! {
-                    macro_strip_builder = macro_strip_builder.size(egui_extras::Size::#gap_fn(#gap_stream));
-                });
+    let separator = info.gap.as_ref().map(|_| match &info.separator {
+        HybridAttribute::Literal(value) => {
+            if value.0 {
+                quote!(strip.cell(|ui| { ui.separator(); });)
+            } else {
+                quote!(strip.empty();)
             }
         }
-    }
+        HybridAttribute::DynamicRust(stream) => {
+            let stream = ctx.attribute(strip, "separator", stream.clone());
+
+            quote! {
+                if #stream {
+                    strip.cell(|ui| {
+                        ui.separator();
+                    });
+                } else {
+                    strip.empty();
+                }
+            }
+        }
+    });
 
-    let direction_ident = match info.direction {
-        DirectionBlueprint::BottomUp => proc_macro2::Ident::new("vertical", Span::call_site()),
-        DirectionBlueprint::LeftToRight => proc_macro2::Ident::new("horizontal", Span::call_site()),
-        DirectionBlueprint::RightToLeft => proc_macro2::Ident::new("horizontal", Span::call_site()),
-        DirectionBlueprint::TopDown => proc_macro2::Ident::new("vertical", Span::call_site()),
+    let cells = Cells {
+        gap: separator,
+        content: true,
+        ..cells
     };
 
-    let mut strip_inner = quote! {};
-
-    for (index, child) in iter.iter().enumerate() {
-        let borrowed_child = child.borrow();
-
-        let children = match borrowed_child.get_children() {
-            Some(children) => children,
-            None => return Err("No Rust allowed here!".to_string()),
-        };
+    let strip_inner = cells.expand(strip, &mut true, &mut |child| {
+        // a slot has no children, its content comes from the macro call
+        if ctx.layout.children(child).is_empty()
+            && !matches!(ctx.layout.element(child), Element::Slot(_))
+        {
+            return Ok(quote!(strip.empty();));
+        }
 
-        if children.is_empty() {
-            strip_inner.append_all(quote!(strip.empty();));
-        } else {
-            let cell_inner = crate::expand_node(child, &ctx)?;
+        let cell_inner = crate::expand_node(child, ctx)?;
 
-            strip_inner.append_all(quote! {
-                strip.cell(|ui| {
-                    #cell_inner
-                });
+        Ok(quote! {
+            strip.cell(|ui| {
+                #cell_inner
             });
-        }
+        })
+    })?;
 
-        if iter.len() - 1 != index {
-            if info.gap.is_some() {
-                match &info.separator {
-                    HybridAttribute::Literal(value) => {
-                        if value.0 {
-                            strip_inner.append_all(quote!(strip.cell(|ui| { ui.separator(); });));
-                        } else {
-                            strip_inner.append_all(quote!(strip.empty();));
-                        }
-                    }
-                    HybridAttribute::DynamicRust(stream) => {
-                        strip_inner.append_all(quote! {
-                            if #stream {
-                                strip.cell(|ui| {
-                                    ui.separator();
-                                });
-                            } else {
-                                strip.empty();
-                            }
-                        });
-                    }
-                }
-            }
-        }
-    }
+    let mut expanded = quote! {
+        #hoisted
+        let mut macro_strip_builder = egui_extras::StripBuilder::new(#ui_var);
+        #counter
+        #sizes
+    };
 
     let strip_stream = quote!(
         let macro_strip_response = macro_strip_builder.#direction_ident (|mut strip| {
+            #counter
             #strip_inner
         });
     );
@@ -0,0 +1,194 @@
+use egui_xml_parser::{
+    element::{Column, Header, Size, Table},
+    Element, NodeId, ParseError,
+};
+use proc_macro2::Span;
+use quote::{quote, TokenStreamExt};
+
+use super::grid::auto_id;
+use super::strip::size_modifiers;
+use crate::XMLContext;
+
+pub fn expand_table(
+    table: NodeId,
+    info: &Table,
+    ctx: &XMLContext,
+) -> Result<proc_macro2::TokenStream, ParseError> {
+    let id = table_id(table, info, ctx);
+    let mut builder = table_builder(table, info, ctx);
+
+    let mut relative = false;
+    let mut header = quote! {};
+    let mut body = quote! { .body(|_| {}) };
+
+    for &child in ctx.layout.children(table) {
+        match ctx.layout.element(child) {
+            Element::Column(column) => {
+                relative |= matches!(column.size, Some(Size::Relative { .. }));
+
+                let column = expand_column(child, column, ctx);
+
+                builder.append_all(quote! { .column(#column) });
+            }
+            Element::Header(info) => header = expand_header(child, info, ctx)?,
+            Element::Body(_) => {
+                let rows = crate::expand_nodes(ctx.layout.children(child), ctx)?;
+
+                body = quote! {
+                    .body(|mut macro_body| {
+                        #rows
+                    })
+                };
+            }
+            _ => {}
+        }
+    }
+
+    // the builder borrows `ui`, relative widths are taken from it before
+    let width = relative.then(|| quote! { let macro_table_width = ui.available_width(); });
+
+    Ok(quote! {
+        {
+            #id
+            #width
+            #builder #header #body;
+        }
+    })
+}
+
+/// Declares `macro_table_id`, the id salt of a `<Table>` or `<DataGrid>`.
+///
+/// The builder borrows `ui`, so the id is taken from it before.
+pub fn table_id(table: NodeId, info: &Table, ctx: &XMLContext) -> proc_macro2::TokenStream {
+    // tables keep their column widths under the id, so every <Table> needs its own
+    let id = match info.id.clone() {
+        Some(id) => ctx.attribute(table, "id", id),
+        None => auto_id("egui_xml_table"),
+    };
+
+    quote! { let macro_table_id = #id; }
+}
+
+/// `egui_extras::TableBuilder` with the table-wide settings, columns are added by the caller.
+///
+/// The caller declares `macro_table_id` with [`table_id`].
+pub fn table_builder(table: NodeId, info: &Table, ctx: &XMLContext) -> proc_macro2::TokenStream {
+    let mut builder = quote! { egui_extras::TableBuilder::new(ui).id_salt(macro_table_id) };
+
+    let modifiers = [
+        ("striped", info.striped.clone()),
+        ("resizable", info.resizable.clone()),
+    ];
+
+    for (attribute, value) in modifiers {
+        if let Some(value) = value {
+            let stream = ctx.attribute(table, attribute, value.into());
+            let method = proc_macro2::Ident::new(attribute, Span::call_site());
+
+            builder.append_all(quote! { .#method(#stream) });
+        }
+    }
+
+    builder
+}
+
+/// `egui_extras::Column` expression for a `<Column>`.
+///
+/// A relative width reads `macro_table_width`, the caller takes it from `ui` before building.
+pub fn expand_column(column: NodeId, info: &Column, ctx: &XMLContext) -> proc_macro2::TokenStream {
+    let mut expanded = match info.size.clone() {
+        None => quote! { egui_extras::Column::auto() },
+        Some(Size::Remainder { min, max }) => {
+            let mut expanded = quote! { egui_extras::Column::remainder() };
+
+            size_modifiers(&mut expanded, column, &min, &max, ctx);
+
+            expanded
+        }
+        Some(Size::Exact { value, min, max }) => {
+            let stream = ctx.attribute(column, "value", value.into());
+
+            let mut expanded = quote! { egui_extras::Column::exact(#stream) };
+
+            size_modifiers(&mut expanded, column, &min, &max, ctx);
+
+            expanded
+        }
+        Some(Size::Relative { value, min, max }) => {
+            let stream = ctx.attribute(column, "value", value.into());
+
+            let mut expanded = quote! { egui_extras::Column::initial(macro_table_width * #stream) };
+
+            size_modifiers(&mut expanded, column, &min, &max, ctx);
+
+            expanded
+        }
+    };
+
+    let modifiers = [
+        ("clip", info.clip.clone()),
+        ("resizable", info.resizable.clone()),
+    ];
+
+    for (attribute, value) in modifiers {
+        if let Some(value) = value {
+            let stream = ctx.attribute(column, attribute, value.into());
+            let method = proc_macro2::Ident::new(attribute, Span::call_site());
+
+            expanded.append_all(quote! { .#method(#stream) });
+        }
+    }
+
+    expanded
+}
+
+fn expand_header(
+    header: NodeId,
+    info: &Header,
+    ctx: &XMLContext,
+) -> Result<proc_macro2::TokenStream, ParseError> {
+    let height = ctx.attribute(header, "height", info.height.clone().into());
+    let cells = crate::expand_nodes(ctx.layout.children(header), ctx)?;
+
+    Ok(quote! {
+        .header(#height, |mut macro_row| {
+            #cells
+        })
+    })
+}
+
+/// A `<Row>` of a table body, as high as the `row_height` of the `<Body>` it is in.
+pub fn expand_table_row(
+    row: NodeId,
+    ctx: &XMLContext,
+) -> Result<proc_macro2::TokenStream, ParseError> {
+    let form = ctx.layout.form();
+
+    let Some((body, Element::Body(info))) = form
+        .ancestors(row)
+        .map(|ancestor| (ancestor, ctx.layout.element(ancestor)))
+        .find(|(_, element)| matches!(element, Element::Body(_)))
+    else {
+        unreachable!("a table row is inside a <Body>");
+    };
+
+    let height = ctx.attribute(body, "row_height", info.row_height.clone().into());
+    let cells = crate::expand_nodes(ctx.layout.children(row), ctx)?;
+
+    Ok(quote! {
+        macro_body.row(#height, |mut macro_row| {
+            #cells
+        });
+    })
+}
+
+/// A `<Cell>`, the contents of one column of a header or body row.
+pub fn expand_cell(cell: NodeId, ctx: &XMLContext) -> Result<proc_macro2::TokenStream, ParseError> {
+    let body = crate::expand_nodes(ctx.layout.children(cell), ctx)?;
+
+    Ok(quote! {
+        macro_row.col(|ui| {
+            #body
+        });
+    })
+}
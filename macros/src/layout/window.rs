@@ -0,0 +1,79 @@
+use egui_xml_parser::{
+    element::{Anchor, Window},
+    NodeId, ParseError,
+};
+use proc_macro2::Span;
+use quote::{quote, TokenStreamExt};
+
+use crate::XMLContext;
+
+pub fn expand_window(
+    window: NodeId,
+    info: &Window,
+    ctx: &XMLContext,
+) -> Result<proc_macro2::TokenStream, ParseError> {
+    let ctx_var = match info.ctx.clone() {
+        Some(ctx_var) => ctx.attribute(window, "ctx", ctx_var),
+        None => quote! { ctx },
+    };
+
+    let title = ctx.attribute(window, "title", info.title.clone());
+
+    let mut builder = quote! { egui::Window::new(#title) };
+
+    if let Some(id) = info.id.clone() {
+        let id = ctx.attribute(window, "id", id);
+
+        builder.append_all(quote! { .id(egui::Id::new(#id)) });
+    }
+
+    if let Some(open) = info.open.clone() {
+        let open = ctx.attribute(window, "open", open);
+
+        builder.append_all(quote! { .open(&mut #open) });
+    }
+
+    let modifiers = [
+        ("resizable", info.resizable.clone().map(Into::into)),
+        ("collapsible", info.collapsible.clone().map(Into::into)),
+        ("default_size", info.default_size.clone().map(Into::into)),
+    ];
+
+    for (attribute, value) in modifiers {
+        if let Some(value) = value {
+            let stream = ctx.attribute(window, attribute, value);
+            let method = proc_macro2::Ident::new(attribute, Span::call_site());
+
+            builder.append_all(quote! { .#method(#stream) });
+        }
+    }
+
+    if let Some(anchor) = info.anchor {
+        let align = match anchor {
+            Anchor::TopLeft => quote! { LEFT_TOP },
+            Anchor::Top => quote! { CENTER_TOP },
+            Anchor::TopRight => quote! { RIGHT_TOP },
+            Anchor::Left => quote! { LEFT_CENTER },
+            Anchor::Center => quote! { CENTER_CENTER },
+            Anchor::Right => quote! { RIGHT_CENTER },
+            Anchor::BottomLeft => quote! { LEFT_BOTTOM },
+            Anchor::Bottom => quote! { CENTER_BOTTOM },
+            Anchor::BottomRight => quote! { RIGHT_BOTTOM },
+        };
+
+        let offset = match info.anchor_offset.clone() {
+            Some(offset) => ctx.attribute(window, "anchor_offset", offset.into()),
+            None => quote! { egui::Vec2::ZERO },
+        };
+
+        builder.append_all(quote! { .anchor(egui::Align2::#align, #offset) });
+    }
+
+    let body = crate::expand_nodes(ctx.layout.children(window), ctx)?;
+
+    Ok(quote! {
+        #builder.show(#ctx_var, |ui| {
+            #body
+        });
+    })
+}
@@ -6,14 +6,14 @@
 //!
 //! ```rust
 //! use eframe::egui;
-//! use egui::{Rounding, Ui};
+//! use egui::{CornerRadius, Ui};
 //! use egui_xml::load_layout;
 //!
 //! struct MyApp;
 //!
 //! fn color_background(ui: &mut Ui, color: egui::Color32) {
 //!     ui.painter()
-//!         .rect_filled(ui.available_rect_before_wrap(), Rounding::same(5.0), color);
+//!         .rect_filled(ui.available_rect_before_wrap(), CornerRadius::same(5), color);
 //! }
 //!
 //! impl eframe::App for MyApp {
@@ -43,26 +43,63 @@
 
 extern crate proc_macro;
 
-use std::{cell::RefCell, rc::Rc};
-
-use egui_xml_parser::{Node, XMLForm};
+use egui_xml_parser::{template, Element, Layout, Node, NodeId, ParseError, XMLForm};
+use layout::border::expand_border;
+use layout::data_grid::expand_data_grid;
+use layout::flow::{expand_case, expand_else, expand_for, expand_if, expand_let, expand_match};
+use layout::grid::{expand_grid, expand_row};
+use layout::panel::{expand_central_panel, expand_edge_panel};
+use layout::scroll_area::expand_scroll_area;
 use layout::strip::expand_strip;
+use layout::table::{expand_cell, expand_table, expand_table_row};
+use layout::window::expand_window;
 use proc_macro::TokenStream;
 
 use quote::{quote, TokenStreamExt};
-use syn::{parse_macro_input, LitStr};
+use slot::{LayoutFileInput, Slots};
+use source::{InlineSource, Input, LayoutFile, LayoutSource};
 
+mod file;
+mod include;
 mod layout;
+mod slot;
+mod source;
+mod tokens;
+
+struct XMLContext<'a> {
+    layout: &'a Layout<'a>,
+    source: &'a LayoutSource<'a>,
+    slots: &'a Slots,
+}
+
+impl XMLContext<'_> {
+    /// The tokens of an attribute of `node`, pointing at its value in the XML.
+    fn attribute(
+        &self,
+        node: NodeId,
+        name: &str,
+        stream: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let Some(attributes) = self.layout.form().node(node).get_attributes() else {
+            return stream;
+        };
 
-struct XMLContext;
+        match attributes.span_of(name) {
+            Some(span) => self
+                .source
+                .attribute(&stream, attributes.location_of(name), span),
+            None => stream,
+        }
+    }
+}
 
 fn expand_nodes(
-    children: &[Rc<RefCell<Node>>],
+    children: &[NodeId],
     ctx: &XMLContext,
-) -> Result<proc_macro2::TokenStream, String> {
+) -> Result<proc_macro2::TokenStream, ParseError> {
     let mut expanded = quote! {};
 
-    for node in children.iter() {
+    for &node in children.iter() {
         let node_expanded = expand_node(node, ctx)?;
         expanded.append_all(node_expanded);
     }
@@ -70,17 +107,60 @@ fn expand_nodes(
     Ok(expanded)
 }
 
-fn expand_node(
-    node: &Rc<RefCell<Node>>,
-    ctx: &XMLContext,
-) -> Result<proc_macro2::TokenStream, String> {
-    match &*node.borrow() {
-        egui_xml_parser::Node::Panel { children, .. } => expand_nodes(children, ctx),
-        egui_xml_parser::Node::Rust { code, .. } => Ok(code.parse().unwrap()),
-        egui_xml_parser::Node::Border { .. } => Ok(quote! {}),
-        egui_xml_parser::Node::Grid { .. } => Ok(quote! {}),
-        egui_xml_parser::Node::Default { children, .. } => expand_nodes(children, ctx),
-        egui_xml_parser::Node::Strip { .. } => expand_strip(node, ctx),
+/// The code for `node` and everything below it.
+///
+/// Unlike the passes over an [`XMLForm`] this is no [`Visit`](egui_xml_parser::visit::Visit),
+/// it matches on the validated [`Element`] of every node and returns the code of the subtree,
+/// which each element wraps around the code of its children.
+fn expand_node(node: NodeId, ctx: &XMLContext) -> Result<proc_macro2::TokenStream, ParseError> {
+    let children = ctx.layout.children(node);
+
+    match ctx.layout.element(node) {
+        Element::Panel(_) => expand_nodes(children, ctx),
+        Element::Rust(stream) => match ctx.layout.form().node(node) {
+            Node::Rust {
+                code,
+                location,
+                span,
+            } => Ok(ctx.source.code(code, stream, *location, *span)),
+            _ => Ok(stream.clone()),
+        },
+        Element::Border(border) => expand_border(node, border, ctx),
+        Element::Grid(grid) => expand_grid(node, grid, ctx),
+        Element::Row => expand_row(node, ctx),
+        Element::EndRow => Ok(quote! { ui.end_row(); }),
+        Element::ScrollArea(area) => expand_scroll_area(node, area, ctx),
+        Element::Window(window) => expand_window(node, window, ctx),
+        Element::EdgePanel(panel) => expand_edge_panel(node, panel, ctx),
+        Element::CentralPanel(panel) => expand_central_panel(node, panel, ctx),
+        Element::Table(table) => expand_table(node, table, ctx),
+        Element::DataGrid(grid) => expand_data_grid(node, grid, ctx),
+        // the builder calls of a table, made by `expand_table` and `expand_data_grid`
+        Element::Column(_) | Element::DataColumn(_) | Element::Header(_) | Element::Body(_) => {
+            Ok(quote! {})
+        }
+        Element::TableRow => expand_table_row(node, ctx),
+        Element::Cell => expand_cell(node, ctx),
+        Element::Root => expand_nodes(children, ctx),
+        Element::Strip(strip) => expand_strip(node, strip, ctx),
+        Element::For(info) => expand_for(node, info, ctx),
+        Element::If(info) | Element::ElseIf(info) => expand_if(node, info, ctx),
+        Element::Else => expand_else(node, ctx),
+        Element::Match(info) => expand_match(node, info, ctx),
+        Element::Case(info) => expand_case(node, info, ctx),
+        Element::Let(info) => expand_let(node, info, ctx),
+        Element::Slot(slot) => match ctx.slots.fill(&slot.name) {
+            Some(content) => Ok(quote! { #content; }),
+            None => Err(ParseError::MissingSlot {
+                slot: slot.name.clone(),
+                location: ctx.layout.form().node(node).location(),
+            }),
+        },
+        // nothing above knows the element, so it cannot become code
+        Element::Unknown(unknown) => Err(ParseError::UnknownElement {
+            element: unknown.name.clone(),
+            location: ctx.layout.form().node(node).location(),
+        }),
     }
 }
 
@@ -88,9 +168,20 @@ fn expand_node(
 ///
 /// This macro parses an XML layout representation and generates Rust code to construct the layout within an egui UI.
 ///
+/// Rust code between the tags is kept as written, so it can use generics, comparisons and
+/// closures. A new tag may only follow a complete statement (`;` or a `{ ... }` block).
+///
+/// Besides `<Strip>` and `<Panel>` there are elements for loops and conditions, grids, frames,
+/// scroll areas, windows, panels and tables, see the
+/// [README](https://github.com/godgotzi/egui_xml#elements) for all of them.
+///
 /// # Example
 ///
 /// ```rust
+/// # use eframe::egui;
+/// # use egui_xml::load_layout;
+/// # fn color_background(ui: &mut egui::Ui, color: egui::Color32) {}
+/// # fn show(ui: &mut egui::Ui) {
 /// load_layout!(
 ///     <Strip direction="west">
 ///         <Panel size="relative" value="0.3">
@@ -108,43 +199,246 @@ fn expand_node(
 ///         </Panel>
 ///     </Strip>
 /// );
+/// # }
 /// ```
 #[proc_macro]
 pub fn load_layout(input: TokenStream) -> TokenStream {
-    let xml = input.to_string();
+    inline_layout(input.into()).into()
+}
 
-    let form: XMLForm = match xml.try_into() {
-        Ok(form) => form,
-        Err(_) => panic!("Failed to load XML"),
+fn inline_layout(input: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let inline = InlineSource::new(input);
+
+    let mut source = LayoutSource {
+        input: Input::Inline(&inline),
+        files: vec![LayoutFile {
+            name: String::new(),
+            path: None,
+            included_at: None,
+        }],
     };
 
-    let ctx = XMLContext;
+    let expanded = tokens::parse_inline(&inline)
+        .and_then(|form| expand_layout(form, &mut source, &Slots::default()));
+
+    match expanded {
+        Ok(expanded) => {
+            let dependencies = source.dependencies();
+
+            quote! {
+                #dependencies
+                #expanded
+            }
+        }
+        Err(e) => source.error(&e).to_compile_error(),
+    }
+}
+
+fn expand_layout(
+    mut form: XMLForm,
+    source: &mut LayoutSource,
+    slots: &Slots,
+) -> Result<proc_macro2::TokenStream, ParseError> {
+    include::expand_includes(&mut form, &mut source.files)?;
+    template::expand_templates(&mut form)?;
 
-    let expanded = match expand_node(&form.root, &ctx) {
-        Ok(expanded) => expanded,
-        Err(e) => panic!("{}", e),
+    let layout = Layout::try_from(&form)?;
+
+    let ctx = XMLContext {
+        layout: &layout,
+        source,
+        slots,
     };
 
-    expanded.into()
+    expand_node(layout.root(), &ctx)
 }
 
 /// Macro for loading layout from a file.
 ///
 /// This macro reads the content of the specified file and passes it to the `load_layout` macro for parsing and code generation.
 ///
+/// Like `include_str!` the path is relative to the file invoking the macro, with the crate root
+/// (`CARGO_MANIFEST_DIR`) as a fallback. The crate is rebuilt whenever the layout file changes.
+///
+/// `<Include src="cells.xml"/>` is replaced by the elements of another file, resolved relative
+/// to the file containing the `<Include>`. It works inline as well, relative to the invoking file.
+///
+/// `<Template name="Quad" params="size">` defines a piece of layout once, every
+/// `<Use template="Quad" size="remainder"/>` is replaced by its content with `$size` filled in.
+///
+/// `<Slot name="sidebar"/>` leaves a place for content passed after the path,
+/// `load_layout_file!("main.xml", sidebar => { self.sidebar(ui) })`. Every slot of the layout
+/// has to be filled, and only those.
+///
+/// Errors in the Rust code of the file point at the path and name the XML location of the code,
+/// e.g. "this error originates in the macro `strip_xml_line_41_col_21`". Code with a top-level
+/// `let` cannot be wrapped this way, the expanded code names its location instead.
+///
 /// # Example
 ///
 /// ```rust
-/// load_layout_file!("layout.xml");
+/// # use eframe::egui;
+/// # use egui_xml::load_layout_file;
+/// # fn show(ui: &mut egui::Ui) {
+/// load_layout_file!("tests/fixtures/hello.xml");
+/// # }
 /// ```
 #[proc_macro]
 pub fn load_layout_file(input: TokenStream) -> TokenStream {
+    file_layout(input.into()).into()
+}
+
+fn file_layout(input: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
     // Parse the input tokens into a syntax tree
-    let input = parse_macro_input!(input as LitStr);
+    let LayoutFileInput { path: input, slots } = match syn::parse2(input) {
+        Ok(input) => input,
+        Err(e) => return e.to_compile_error(),
+    };
     let file_path = input.value();
 
-    let file_content =
-        std::fs::read_to_string(&file_path).expect(&format!("unable to find {}", file_path));
+    let resolved = match file::resolve(&file_path, None) {
+        Ok(resolved) => resolved,
+        Err(tried) => {
+            let tried: Vec<String> = tried
+                .iter()
+                .map(|path| format!("\n    {}", path.display()))
+                .collect();
+
+            let message = format!(
+                "layout file {} not found, tried:{}",
+                file_path,
+                tried.join("")
+            );
+
+            return syn::Error::new(input.span(), message).to_compile_error();
+        }
+    };
+
+    let file_content = match std::fs::read_to_string(&resolved) {
+        Ok(file_content) => file_content,
+        Err(e) => {
+            let message = format!("unable to read {}: {}", resolved.display(), e);
+
+            return syn::Error::new(input.span(), message).to_compile_error();
+        }
+    };
+
+    let mut source = LayoutSource {
+        input: Input::File(input.span()),
+        files: vec![LayoutFile {
+            name: file_path,
+            path: Some(resolved),
+            included_at: None,
+        }],
+    };
+
+    let expanded =
+        XMLForm::try_from(file_content).and_then(|form| expand_layout(form, &mut source, &slots));
+
+    if let (Ok(_), Some(error)) = (&expanded, slots.unused()) {
+        return error.to_compile_error();
+    }
+
+    match expanded {
+        Ok(expanded) => {
+            let dependencies = source.dependencies();
+
+            quote! {
+                #dependencies
+                #expanded
+            }
+        }
+        Err(e) => source.error(&e).to_compile_error(),
+    }
+}
+
+/// The message of the `compile_error!` in `output`, with the line and 0-based column it points at.
+#[cfg(test)]
+fn compile_error(output: &proc_macro2::TokenStream) -> Option<(String, usize, usize)> {
+    use proc_macro2::TokenTree;
+
+    let tokens: Vec<TokenTree> = output.clone().into_iter().collect();
+
+    let position = tokens
+        .iter()
+        .position(|token| matches!(token, TokenTree::Ident(ident) if ident == "compile_error"))?;
+
+    let TokenTree::Group(group) = tokens.get(position + 2)? else {
+        return None;
+    };
+
+    let message = syn::parse2::<syn::LitStr>(group.stream()).ok()?.value();
+    let start = tokens[0].span().start();
 
-    load_layout(file_content.parse().unwrap())
+    Some((message, start.line, start.column))
+}
+
+mod test {
+    #[test]
+    fn test_inline_error() {
+        use super::{compile_error, inline_layout};
+
+        let input = "<Strip direction=\"north\">\n    <Panel size=\"big\"></Panel>\n</Strip>";
+
+        assert_eq!(
+            compile_error(&inline_layout(input.parse().unwrap())),
+            Some((
+                "invalid value \"big\" for attribute `size` of <Panel>".to_string(),
+                2,
+                11
+            ))
+        );
+
+        let input = "<Strip direction=\"north\">\n    <Panel size=\"remainder\">\n</Strip>";
+
+        assert_eq!(
+            compile_error(&inline_layout(input.parse().unwrap())),
+            Some((
+                "malformed XML: expected `</Panel>`, but `</Strip>` found".to_string(),
+                3,
+                0
+            ))
+        );
+    }
+
+    #[test]
+    fn test_file_error() {
+        use super::{compile_error, file_layout};
+
+        // file errors name the XML location and point at the path
+        assert_eq!(
+            compile_error(&file_layout("\"tests/fixtures/invalid.xml\"".parse().unwrap())),
+            Some((
+                "tests/fixtures/invalid.xml:2:12: invalid value \"big\" for attribute `size` of <Panel>"
+                    .to_string(),
+                1,
+                0
+            ))
+        );
+
+        let (message, line, column) = compile_error(&file_layout(
+            "\n  \"tests/fixtures/missing.xml\"".parse().unwrap(),
+        ))
+        .unwrap();
+
+        assert!(message.starts_with("layout file tests/fixtures/missing.xml not found, tried:"));
+        assert_eq!((line, column), (2, 2));
+    }
+
+    #[test]
+    fn test_file_code() {
+        use super::file_layout;
+
+        let output = file_layout("\"tests/fixtures/code.xml\"".parse().unwrap()).to_string();
+
+        // blocks are wrapped in a macro named after their location, those with a `let` follow it
+        assert!(output.contains("macro_rules ! code_xml_line_3_col_11"));
+        assert!(!output.contains("code_xml_line_2_col_11"));
+        assert!(!output.contains("code_xml_line_6_col_11"));
+        assert!(output
+            .contains("const _ : & str = \"tests/fixtures/code.xml:2:11\" ; let mut count = 1 ;"));
+        assert!(output.contains(
+            "const _ : & str = \"tests/fixtures/code.xml:6:11\" ; let label = format ! (\"{count}\") ;"
+        ));
+    }
 }
@@ -0,0 +1,171 @@
+use std::cell::RefCell;
+
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use syn::{
+    parse::{Parse, ParseStream},
+    Expr, Ident, LitStr, Token,
+};
+
+/// The input of `load_layout_file!`: the path, then the content for every `<Slot>`.
+pub struct LayoutFileInput {
+    pub path: LitStr,
+    pub slots: Slots,
+}
+
+impl Parse for LayoutFileInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        let mut slots = Slots::default();
+
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+
+            if input.is_empty() {
+                break;
+            }
+
+            let name: Ident = input.parse()?;
+            input.parse::<Token![=>]>()?;
+            let content: Expr = input.parse()?;
+
+            if slots.fillers.iter().any(|(filled, _)| *filled == name) {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!("slot `{}` is filled twice", name),
+                ));
+            }
+
+            slots.fillers.push((name, content));
+            slots.used.borrow_mut().push(false);
+        }
+
+        Ok(LayoutFileInput { path, slots })
+    }
+}
+
+/// Content passed in for the `<Slot>`s of a layout, `sidebar => { ... }`.
+#[derive(Default)]
+pub struct Slots {
+    fillers: Vec<(Ident, Expr)>,
+    /// Which fillers a `<Slot>` asked for, the rest has no place in the layout.
+    used: RefCell<Vec<bool>>,
+}
+
+impl Slots {
+    /// The content for the slot `name`.
+    pub fn fill(&self, name: &str) -> Option<TokenStream> {
+        let index = self.fillers.iter().position(|(filled, _)| filled == name)?;
+
+        self.used.borrow_mut()[index] = true;
+
+        Some(self.fillers[index].1.to_token_stream())
+    }
+
+    /// An error for every filler the layout has no `<Slot>` for.
+    pub fn unused(&self) -> Option<syn::Error> {
+        self.fillers
+            .iter()
+            .zip(self.used.borrow().iter())
+            .filter(|(_, used)| !**used)
+            .map(|((name, _), _)| {
+                syn::Error::new(name.span(), format!("the layout has no slot `{}`", name))
+            })
+            .reduce(|mut errors, error| {
+                errors.combine(error);
+                errors
+            })
+    }
+}
+
+mod test {
+    #[test]
+    fn test_slots_fill() {
+        use super::LayoutFileInput;
+
+        let input = syn::parse2::<LayoutFileInput>(
+            "\"main.xml\", sidebar => { self.sidebar(ui) }, content => ui.label(\"hi\"),"
+                .parse()
+                .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(input.path.value(), "main.xml");
+        assert_eq!(
+            input
+                .slots
+                .fill("sidebar")
+                .map(|content| content.to_string()),
+            Some("{ self . sidebar (ui) }".to_string())
+        );
+
+        // a slot without content is reported by the layout, which knows where the slot is
+        assert!(input.slots.fill("footer").is_none());
+
+        assert!(input.slots.fill("content").is_some());
+        assert!(input.slots.unused().is_none());
+    }
+
+    #[test]
+    fn test_slots_missing() {
+        use crate::{compile_error, file_layout};
+
+        assert_eq!(
+            compile_error(&file_layout(
+                "\"tests/fixtures/slots.xml\", sidebar => {}".parse().unwrap()
+            )),
+            Some((
+                "tests/fixtures/slots.xml:3:5: slot `content` is not filled, pass `content => { ... }` to the macro"
+                    .to_string(),
+                1,
+                0
+            ))
+        );
+    }
+
+    #[test]
+    fn test_slots_unused() {
+        use super::LayoutFileInput;
+
+        let input = syn::parse2::<LayoutFileInput>(
+            "\"main.xml\", sidebar => {}, footer => {}, header => {}"
+                .parse()
+                .unwrap(),
+        )
+        .unwrap();
+
+        input.slots.fill("sidebar");
+
+        let errors: Vec<(String, usize)> = input
+            .slots
+            .unused()
+            .unwrap()
+            .into_iter()
+            .map(|error| (error.to_string(), error.span().start().column))
+            .collect();
+
+        assert_eq!(
+            errors,
+            vec![
+                ("the layout has no slot `footer`".to_string(), 27),
+                ("the layout has no slot `header`".to_string(), 41),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_slots_duplicate() {
+        use super::LayoutFileInput;
+
+        let error = syn::parse2::<LayoutFileInput>(
+            "\"main.xml\", sidebar => {}, sidebar => {}"
+                .parse()
+                .unwrap(),
+        )
+        .err()
+        .unwrap();
+
+        assert_eq!(error.to_string(), "slot `sidebar` is filled twice");
+        assert_eq!(error.span().start().column, 27);
+    }
+}
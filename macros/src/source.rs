@@ -0,0 +1,297 @@
+use std::path::{Path, PathBuf};
+
+use egui_xml_parser::{Location, ParseError};
+use proc_macro2::{Delimiter, Group, Ident, Spacing, Span, TokenStream, TokenTree};
+use quote::{quote, quote_spanned};
+
+/// The tokens of an inline `load_layout!` call written out as text, with the span of every token.
+///
+/// Locations in the parsed form point into this text. Parse errors only know such a position,
+/// this maps them back onto the macro input so the diagnostic underlines the offending tokens.
+pub struct InlineSource {
+    text: String,
+    spans: Vec<(usize, usize, Span)>,
+    /// Every top-level token with its range in `text`.
+    trees: Vec<(usize, usize, TokenTree)>,
+}
+
+impl InlineSource {
+    pub fn new(input: TokenStream) -> Self {
+        let mut source = InlineSource {
+            text: String::new(),
+            spans: Vec::new(),
+            trees: Vec::new(),
+        };
+
+        for token in input {
+            let start = source.text.len();
+            source.push_stream(TokenStream::from(token.clone()));
+
+            let end = source.spans.last().map(|(_, end, _)| *end).unwrap_or(start);
+            source.trees.push((start, end, token));
+        }
+
+        source
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn trees(&self) -> &[(usize, usize, TokenTree)] {
+        &self.trees
+    }
+
+    /// The span of the token at `location`, or of the first token after it.
+    pub fn span_at(&self, location: Location) -> Span {
+        self.span_at_offset(location.to_offset(&self.text))
+    }
+
+    fn span_at_offset(&self, offset: usize) -> Span {
+        self.spans
+            .iter()
+            .find(|(_, end, _)| offset < *end)
+            .or(self.spans.last())
+            .map(|(_, _, span)| *span)
+            .unwrap_or_else(Span::call_site)
+    }
+
+    /// The original tokens of the Rust code at `span`, if the XML text there is exactly `code`.
+    fn tokens_in(&self, span: egui_xml_parser::Span, code: &str) -> Option<TokenStream> {
+        if self.text.get(span.start..span.end) != Some(code) {
+            return None;
+        }
+
+        let trees: Vec<&(usize, usize, TokenTree)> = self
+            .trees
+            .iter()
+            .filter(|(start, end, _)| *start >= span.start && *end <= span.end)
+            .collect();
+
+        match (trees.first(), trees.last()) {
+            (Some((start, _, _)), Some((_, end, _)))
+                if *start == span.start && *end == span.end =>
+            {
+                Some(trees.iter().map(|(_, _, token)| token.clone()).collect())
+            }
+            _ => None,
+        }
+    }
+
+    fn push_stream(&mut self, stream: TokenStream) {
+        for token in stream {
+            match token {
+                TokenTree::Group(group) => {
+                    let (open, close) = match group.delimiter() {
+                        Delimiter::Parenthesis => ("(", ")"),
+                        Delimiter::Brace => ("{", "}"),
+                        Delimiter::Bracket => ("[", "]"),
+                        Delimiter::None => ("", ""),
+                    };
+
+                    self.push(open, group.span_open(), true);
+                    self.push_stream(group.stream());
+                    self.push(close, group.span_close(), true);
+                }
+                TokenTree::Punct(punct) => {
+                    // `<Panel`, `</Panel` and `/>` stay glued together, so the text reads like XML
+                    let glued = punct.spacing() == Spacing::Joint
+                        || punct.as_char() == '<'
+                        || (punct.as_char() == '/' && self.text.ends_with('<'));
+
+                    self.push(&punct.as_char().to_string(), punct.span(), !glued);
+                }
+                TokenTree::Ident(ident) => self.push(&ident.to_string(), ident.span(), true),
+                TokenTree::Literal(literal) => {
+                    self.push(&literal.to_string(), literal.span(), true)
+                }
+            }
+        }
+    }
+
+    fn push(&mut self, text: &str, span: Span, space_after: bool) {
+        let start = self.text.len();
+        self.text.push_str(text);
+        self.spans.push((start, self.text.len(), span));
+
+        if space_after {
+            self.text.push(' ');
+        }
+    }
+}
+
+/// A layout file read by `load_layout_file!` or an `<Include>`.
+pub struct LayoutFile {
+    /// The path as written in the macro call or the `src` attribute.
+    pub name: String,
+    /// Where the file was found, `None` for the tokens of an inline layout.
+    pub path: Option<PathBuf>,
+    /// The `<Include>` that pulled the file in.
+    pub included_at: Option<Location>,
+}
+
+/// The macro input a layout was written in.
+pub enum Input<'a> {
+    Inline(&'a InlineSource),
+    /// The path literal of `load_layout_file!`.
+    File(Span),
+}
+
+/// Where the XML of a layout comes from, used to point generated code and errors back at it.
+pub struct LayoutSource<'a> {
+    pub input: Input<'a>,
+    /// Every source of the layout, [`Location::file`] is an index into it.
+    /// The first one is the macro input itself.
+    pub files: Vec<LayoutFile>,
+}
+
+impl LayoutSource<'_> {
+    /// The span in the macro input for `location`.
+    ///
+    /// Locations in included files map to the `<Include>` they came from.
+    pub fn span_at(&self, mut location: Location) -> Span {
+        while let Some(included_at) = self
+            .files
+            .get(location.file)
+            .and_then(|file| file.included_at)
+        {
+            location = included_at;
+        }
+
+        match &self.input {
+            Input::Inline(source) => source.span_at(location),
+            Input::File(span) => *span,
+        }
+    }
+
+    pub fn error(&self, error: &ParseError) -> syn::Error {
+        let location = error.location();
+
+        // errors in files name the file, errors in inline tokens are underlined in place
+        match self.files.get(location.file) {
+            Some(file) if file.path.is_some() => {
+                syn::Error::new(self.span_at(location), format!("{}:{}", file.name, error))
+            }
+            _ => syn::Error::new(self.span_at(location), error.message()),
+        }
+    }
+
+    /// The tokens of a Rust block at `location`, covering `span` in the XML.
+    pub fn code(
+        &self,
+        code: &str,
+        stream: &TokenStream,
+        location: Location,
+        span: egui_xml_parser::Span,
+    ) -> TokenStream {
+        if let (Input::Inline(source), 0) = (&self.input, location.file) {
+            return source
+                .tokens_in(span, code)
+                .unwrap_or_else(|| respan(stream, source.span_at_offset(span.start)));
+        }
+
+        let span = self.span_at(location);
+        let stream = respan(stream, span);
+
+        // hygiene would hide the bindings of a top-level `let` from the code after it,
+        // and a `$` would be read as a metavariable, such a block is only preceded by its
+        // location, `const _: &str = "strip.xml:41:9";`
+        let binds = stream
+            .clone()
+            .into_iter()
+            .any(|token| matches!(&token, TokenTree::Ident(ident) if ident == "let"));
+
+        if binds || stream.to_string().contains('$') {
+            let file = &self.files[location.file].name;
+            let marker = format!("{}:{}:{}", file, location.line, location.column);
+
+            return quote_spanned! {span=>
+                const _: &str = #marker;
+                #stream
+            };
+        }
+
+        // rustc names the macro in every diagnostic inside the block,
+        // "this error originates in the macro `strip_xml_line_41_col_9`"
+        let name = Ident::new(
+            &format!(
+                "{}_line_{}_col_{}",
+                file_ident(&self.files[location.file].name),
+                location.line,
+                location.column
+            ),
+            span,
+        );
+
+        quote_spanned! {span=>
+            macro_rules! #name {
+                () => { #stream };
+            }
+
+            #name! {}
+        }
+    }
+
+    /// The tokens of an attribute value at `location`, covering `span` in the XML.
+    pub fn attribute(
+        &self,
+        stream: &TokenStream,
+        location: Location,
+        span: egui_xml_parser::Span,
+    ) -> TokenStream {
+        match (&self.input, location.file) {
+            (Input::Inline(source), 0) => respan(stream, source.span_at_offset(span.start)),
+            _ => respan(stream, self.span_at(location)),
+        }
+    }
+
+    /// Makes every file of the layout a dependency of the crate, so editing one triggers a rebuild.
+    pub fn dependencies(&self) -> TokenStream {
+        self.files
+            .iter()
+            .filter_map(|file| file.path.as_ref())
+            .map(|path| {
+                let path = path.to_string_lossy();
+
+                quote! { const _: &str = include_str!(#path); }
+            })
+            .collect()
+    }
+}
+
+/// `tests/strip.xml` becomes `strip_xml`.
+fn file_ident(path: &str) -> String {
+    let name = Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let ident: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    match ident.chars().next() {
+        Some(c) if c.is_ascii_alphabetic() => ident,
+        _ => format!("xml_{}", ident),
+    }
+}
+
+fn respan(stream: &TokenStream, span: Span) -> TokenStream {
+    stream
+        .clone()
+        .into_iter()
+        .map(|token| match token {
+            TokenTree::Group(group) => {
+                let mut respanned = Group::new(group.delimiter(), respan(&group.stream(), span));
+                respanned.set_span(span);
+
+                TokenTree::Group(respanned)
+            }
+            mut token => {
+                token.set_span(span);
+                token
+            }
+        })
+        .collect()
+}
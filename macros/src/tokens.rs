@@ -0,0 +1,335 @@
+use egui_xml_parser::{Attributes, Location, Node, NodeId, ParseError, Span, XMLForm};
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use syn::LitStr;
+
+use crate::source::InlineSource;
+
+enum Tag {
+    Open(Node),
+    Empty(Node),
+    Close(String),
+}
+
+/// Builds the form of an inline `load_layout!` straight from its tokens.
+///
+/// Everything between two tags is a Rust block and keeps its original tokens, so it may use
+/// generics, comparisons or closures. A `<` only opens a tag at the start of a statement, i.e.
+/// right after another tag, a `;` or a `{ ... }` block, while `</` always closes one.
+pub fn parse_inline(source: &InlineSource) -> Result<XMLForm, ParseError> {
+    let trees = source.trees();
+
+    let mut form = XMLForm::new();
+    let mut current_node = form.root();
+
+    // index of the first token of the Rust block being read
+    let mut code_start: Option<usize> = None;
+    let mut index = 0;
+
+    while index < trees.len() {
+        // the content of a <Rust> element is code up to its closing tag
+        let open_allowed = !matches!(form.node(current_node), Node::Rust { .. })
+            && code_start.is_none_or(|_| ends_statement(&trees[index - 1].2));
+
+        let Some((tag, next)) = parse_tag(source, index, open_allowed)? else {
+            code_start.get_or_insert(index);
+            index += 1;
+            continue;
+        };
+
+        if let Some(start) = code_start.take() {
+            push_code(&mut form, current_node, source, start, index);
+        }
+
+        match tag {
+            Tag::Open(node) => current_node = form.append(current_node, node),
+            Tag::Empty(node) => {
+                form.append(current_node, node);
+            }
+            Tag::Close(name) => {
+                let open = form.node(current_node).name();
+
+                match form.parent(current_node) {
+                    Some(parent) if open == name => current_node = parent,
+                    Some(_) => {
+                        return Err(malformed(
+                            source,
+                            index,
+                            format!("expected `</{}>`, but `</{}>` found", open, name),
+                        ))
+                    }
+                    None => {
+                        return Err(malformed(
+                            source,
+                            index,
+                            format!("unexpected `</{}>`", name),
+                        ))
+                    }
+                }
+            }
+        }
+
+        index = next;
+    }
+
+    if let Some(start) = code_start {
+        push_code(&mut form, current_node, source, start, trees.len());
+    }
+
+    if form.parent(current_node).is_some() {
+        return Err(ParseError::MalformedXml {
+            message: format!("unclosed `<{}>`", form.node(current_node).name()),
+            location: form.node(current_node).location(),
+        });
+    }
+
+    Ok(form)
+}
+
+/// Reads the tag starting at `index`, if there is one.
+fn parse_tag(
+    source: &InlineSource,
+    index: usize,
+    open_allowed: bool,
+) -> Result<Option<(Tag, usize)>, ParseError> {
+    let trees = source.trees();
+    let token = |index: usize| trees.get(index).map(|(_, _, token)| token);
+
+    if !is_punct(token(index), '<') {
+        return Ok(None);
+    }
+
+    if is_punct(token(index + 1), '/') {
+        return match (token(index + 2), token(index + 3)) {
+            (Some(TokenTree::Ident(name)), close) if is_punct(close, '>') => {
+                Ok(Some((Tag::Close(name.to_string()), index + 4)))
+            }
+            _ => Err(malformed(source, index, "expected `</Name>`".to_string())),
+        };
+    }
+
+    let name = match token(index + 1) {
+        Some(TokenTree::Ident(name)) if open_allowed => name.to_string(),
+        _ => return Ok(None),
+    };
+
+    let location = Location::from_offset(source.text(), trees[index].0);
+    let mut attributes = Attributes::new(&name, location);
+
+    let mut next = index + 2;
+
+    loop {
+        match token(next) {
+            Some(close) if is_punct(Some(close), '>') => {
+                return Ok(Some((
+                    Tag::Open(Node::from_element(name, attributes)),
+                    next + 1,
+                )));
+            }
+            Some(slash) if is_punct(Some(slash), '/') && is_punct(token(next + 1), '>') => {
+                return Ok(Some((
+                    Tag::Empty(Node::from_element(name, attributes)),
+                    next + 2,
+                )));
+            }
+            Some(TokenTree::Ident(attribute)) if is_punct(token(next + 1), '=') => {
+                let Some(TokenTree::Literal(literal)) = token(next + 2) else {
+                    return Err(malformed(
+                        source,
+                        next,
+                        format!("attribute `{}` has no string value", attribute),
+                    ));
+                };
+
+                let Ok(value) =
+                    syn::parse2::<LitStr>(TokenStream::from(TokenTree::Literal(literal.clone())))
+                else {
+                    return Err(malformed(
+                        source,
+                        next + 2,
+                        format!("the value of attribute `{}` must be a string", attribute),
+                    ));
+                };
+
+                let attribute = attribute.to_string();
+
+                if attributes.get(&attribute).is_some() {
+                    return Err(malformed(
+                        source,
+                        next,
+                        format!("duplicated attribute `{}`", attribute),
+                    ));
+                }
+
+                attributes.insert(
+                    attribute,
+                    value.value(),
+                    Location::from_offset(source.text(), trees[next].0),
+                    value_span(source, next + 2),
+                );
+
+                next += 3;
+            }
+            Some(_) => {
+                return Err(malformed(
+                    source,
+                    next,
+                    format!("expected an attribute, `>` or `/>` in `<{}>`", name),
+                ))
+            }
+            None => return Err(malformed(source, index, format!("unclosed `<{}`", name))),
+        }
+    }
+}
+
+fn push_code(form: &mut XMLForm, parent: NodeId, source: &InlineSource, start: usize, end: usize) {
+    let trees = source.trees();
+
+    let span = Span::new(trees[start].0, trees[end - 1].1);
+    let code = source.text()[span.start..span.end].to_string();
+    let location = Location::from_offset(source.text(), span.start);
+
+    form.append_code(parent, code, location, span);
+}
+
+/// The text range of a string literal without its quotes.
+fn value_span(source: &InlineSource, index: usize) -> Span {
+    let (start, end, _) = &source.trees()[index];
+    let literal = &source.text()[*start..*end];
+
+    match (literal.find('"'), literal.rfind('"')) {
+        (Some(open), Some(close)) if open < close => Span::new(start + open + 1, start + close),
+        _ => Span::new(*start, *end),
+    }
+}
+
+fn ends_statement(token: &TokenTree) -> bool {
+    match token {
+        TokenTree::Punct(punct) => punct.as_char() == ';',
+        TokenTree::Group(group) => group.delimiter() == Delimiter::Brace,
+        _ => false,
+    }
+}
+
+fn is_punct(token: Option<&TokenTree>, c: char) -> bool {
+    matches!(token, Some(TokenTree::Punct(punct)) if punct.as_char() == c)
+}
+
+fn malformed(source: &InlineSource, index: usize, message: String) -> ParseError {
+    let offset = source
+        .trees()
+        .get(index)
+        .map(|(start, _, _)| *start)
+        .unwrap_or(source.text().len());
+
+    ParseError::MalformedXml {
+        message,
+        location: Location::from_offset(source.text(), offset),
+    }
+}
+
+/// The error of parsing `input`, with the line and 0-based column in `input` it points at.
+#[cfg(test)]
+fn parse_error(input: &str) -> (String, usize, usize) {
+    let source = InlineSource::new(input.parse().unwrap());
+    let error = parse_inline(&source).unwrap_err();
+    let start = source.span_at(error.location()).start();
+
+    (error.message(), start.line, start.column)
+}
+
+mod test {
+    #[test]
+    fn test_parse_inline_code() {
+        use super::parse_inline;
+        use crate::source::InlineSource;
+        use egui_xml_parser::Node;
+        use proc_macro2::TokenStream;
+
+        let code = "let v: Vec<u8> = Vec::new(); if a < b { x::<u8>(); } let c = a<b;";
+        let input = format!("<Strip><Panel>{}</Panel><Panel/></Strip>", code);
+
+        let form = parse_inline(&InlineSource::new(input.parse().unwrap())).unwrap();
+
+        let strip = form.children(form.root())[0];
+        let panels = form.children(strip);
+
+        assert_eq!(panels.len(), 2);
+        assert_eq!(form.children(panels[0]).len(), 1);
+        assert!(form.children(panels[1]).is_empty());
+
+        let Node::Rust { code: parsed, .. } = form.node(form.children(panels[0])[0]) else {
+            panic!("expected a Rust block");
+        };
+
+        assert_eq!(
+            parsed.parse::<TokenStream>().unwrap().to_string(),
+            code.parse::<TokenStream>().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn test_parse_inline_mismatched() {
+        use super::parse_error;
+
+        assert_eq!(
+            parse_error("<Strip>\n    <Panel>\n    </Strip>"),
+            (
+                "malformed XML: expected `</Panel>`, but `</Strip>` found".to_string(),
+                3,
+                4
+            )
+        );
+
+        assert_eq!(
+            parse_error("<Panel></Panel>\n</Strip>"),
+            ("malformed XML: unexpected `</Strip>`".to_string(), 2, 0)
+        );
+    }
+
+    #[test]
+    fn test_parse_inline_unclosed() {
+        use super::parse_error;
+
+        assert_eq!(
+            parse_error("<Strip>\n    <Panel></Panel>"),
+            ("malformed XML: unclosed `<Strip>`".to_string(), 1, 0)
+        );
+
+        assert_eq!(
+            parse_error("<Strip>\n    <Panel size=\"remainder\""),
+            ("malformed XML: unclosed `<Panel`".to_string(), 2, 4)
+        );
+    }
+
+    #[test]
+    fn test_parse_inline_attributes() {
+        use super::parse_error;
+
+        assert_eq!(
+            parse_error("<Strip>\n    <Panel size=\"exact\" size=\"remainder\"></Panel>\n</Strip>"),
+            (
+                "malformed XML: duplicated attribute `size`".to_string(),
+                2,
+                24
+            )
+        );
+
+        assert_eq!(
+            parse_error("<Panel size=4></Panel>"),
+            (
+                "malformed XML: the value of attribute `size` must be a string".to_string(),
+                1,
+                12
+            )
+        );
+
+        assert_eq!(
+            parse_error("<Panel size></Panel>"),
+            (
+                "malformed XML: expected an attribute, `>` or `/>` in `<Panel>`".to_string(),
+                1,
+                7
+            )
+        );
+    }
+}
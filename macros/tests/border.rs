@@ -0,0 +1,25 @@
+use eframe::egui;
+use egui_xml::load_layout;
+
+/// The children of a `<Border>` are laid out inside its margins.
+#[test]
+fn test_border_margins() {
+    let ctx = egui::Context::default();
+    let mut outer = 0.0;
+    let mut inner = 0.0;
+
+    let _ = ctx.run(Default::default(), |ctx| {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            outer = ui.available_width();
+
+            load_layout!(
+                <Border stroke="1.0" color="#444" fill="#20202080" rounding="4" inner_margin="6" outer_margin="2">
+                    inner = ui.available_width();
+                </Border>
+            );
+        });
+    });
+
+    assert!(inner > 0.0);
+    assert!(outer - inner >= 2.0 * (6.0 + 2.0));
+}
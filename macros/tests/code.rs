@@ -0,0 +1,23 @@
+use eframe::egui;
+use egui_xml::load_layout_file;
+
+/// Rust blocks of a file are wrapped to name their XML location in diagnostics,
+/// locals and labels still reach from one block into the next and from the invoking code.
+/// The first frame skips the rest of the layout, only the second one adds up the count.
+fn show(ui: &mut egui::Ui, counted: &mut i32) {
+    'frames: for frame in 0..2 {
+        load_layout_file!("tests/fixtures/code.xml");
+    }
+}
+
+#[test]
+fn test_code_scopes() {
+    let ctx = egui::Context::default();
+    let mut counted = 0;
+
+    let _ = ctx.run(Default::default(), |ctx| {
+        egui::CentralPanel::default().show(ctx, |ui| show(ui, &mut counted));
+    });
+
+    assert_eq!(counted, 3);
+}
@@ -0,0 +1,154 @@
+use std::cell::Cell;
+
+use eframe::egui;
+use egui_xml::{load_layout, SortState};
+
+/// The rows of a `<DataGrid>` are sorted every frame, so a changed field reorders them.
+#[test]
+fn test_data_grid_sorts_changed_rows() {
+    let ctx = egui::Context::default();
+    let mut rows = vec![2, 3, 1];
+    let mut sort = SortState {
+        column: Some(0),
+        descending: false,
+    };
+    let mut shown = Vec::new();
+
+    let mut frame = |rows: &[i32]| {
+        shown.clear();
+
+        let _ = ctx.run(Default::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                load_layout!(
+                    <DataGrid rows="@rows" sort="@sort" as="row">
+                        <Column field="@*row" sortable="true">
+                            shown.push(*row);
+                        </Column>
+                    </DataGrid>
+                );
+            });
+        });
+
+        shown.clone()
+    };
+
+    assert_eq!(frame(&rows), vec![1, 2, 3]);
+
+    rows[2] = 4;
+
+    assert_eq!(frame(&rows), vec![2, 3, 4]);
+
+    rows = vec![0, 9, 5];
+
+    assert_eq!(frame(&rows), vec![0, 5, 9]);
+}
+
+/// With a `version` the rows of a `<DataGrid>` are sorted again only when it, the sort state or
+/// their number changes.
+#[test]
+fn test_data_grid_sort_cache() {
+    let ctx = egui::Context::default();
+    let mut rows = vec![2, 3, 1];
+    let mut version = 0;
+    let mut sort = SortState {
+        column: Some(0),
+        descending: true,
+    };
+    let compared = Cell::new(0);
+    let mut shown = Vec::new();
+
+    let frame = |rows: &[i32], version: u32, sort: &mut SortState, shown: &mut Vec<i32>| {
+        shown.clear();
+
+        let _ = ctx.run(Default::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                load_layout!(
+                    <DataGrid rows="@rows" sort="@*sort" version="@version" as="row">
+                        <Column field="@{ compared.set(compared.get() + 1); *row }" sortable="true">
+                            shown.push(*row);
+                        </Column>
+                    </DataGrid>
+                );
+            });
+        });
+    };
+
+    frame(&rows, version, &mut sort, &mut shown);
+
+    let sorted = compared.get();
+
+    assert!(sorted > 0);
+    assert_eq!(shown, vec![3, 2, 1]);
+
+    frame(&rows, version, &mut sort, &mut shown);
+
+    assert_eq!(compared.get(), sorted);
+    assert_eq!(shown, vec![3, 2, 1]);
+
+    sort.descending = false;
+    frame(&rows, version, &mut sort, &mut shown);
+
+    assert!(compared.get() > sorted);
+    assert_eq!(shown, vec![1, 2, 3]);
+
+    rows.push(0);
+    frame(&rows, version, &mut sort, &mut shown);
+
+    assert_eq!(shown, vec![0, 1, 2, 3]);
+
+    // a changed field keeps its place until the version changes
+    rows[0] = 5;
+    frame(&rows, version, &mut sort, &mut shown);
+
+    assert_eq!(shown, vec![0, 1, 5, 3]);
+
+    version += 1;
+    frame(&rows, version, &mut sort, &mut shown);
+
+    assert_eq!(shown, vec![0, 1, 3, 5]);
+}
+
+struct Order {
+    id: u32,
+    customer: Customer,
+}
+
+struct Customer {
+    name: &'static str,
+}
+
+/// The columns of a `<DataGrid>` show a field path, Rust code or their children for every row.
+#[test]
+fn test_data_grid_fields() {
+    let ctx = egui::Context::default();
+    let orders = vec![
+        Order {
+            id: 1,
+            customer: Customer { name: "Ada" },
+        },
+        Order {
+            id: 2,
+            customer: Customer { name: "Charles" },
+        },
+    ];
+    let mut shown = Vec::new();
+
+    let _ = ctx.run(Default::default(), |ctx| {
+        shown.clear();
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            load_layout!(
+                <DataGrid rows="@orders" as="order">
+                    <Column header="Id" field="id"/>
+                    <Column header="Customer" field="customer.name"/>
+                    <Column header="Twice" field="@order.id * 2"/>
+                    <Column header="Shown">
+                        shown.push(order.customer.name);
+                    </Column>
+                </DataGrid>
+            );
+        });
+    });
+
+    assert_eq!(shown, vec!["Ada", "Charles"]);
+}
@@ -0,0 +1,33 @@
+use eframe::egui;
+use egui_xml::load_layout;
+
+/// Grids without an id are shown side by side, each `<Row>` ending a row of its grid.
+#[test]
+fn test_grid_rows() {
+    let ctx = egui::Context::default();
+    let mut cells = Vec::new();
+
+    let _ = ctx.run(Default::default(), |ctx| {
+        cells.clear();
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            load_layout!(
+                <Grid columns="2" striped="true">
+                    <For each="@[1, 2]" as="number">
+                        <Row>
+                            ui.label(number.to_string());
+                            cells.push(number);
+                        </Row>
+                    </For>
+                </Grid>
+                <Grid spacing="8,4">
+                    ui.label("3");
+                    cells.push(3);
+                    <EndRow/>
+                </Grid>
+            );
+        });
+    });
+
+    assert_eq!(cells, vec![1, 2, 3]);
+}
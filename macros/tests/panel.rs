@@ -0,0 +1,41 @@
+use eframe::egui;
+use egui_xml::load_layout;
+
+/// Panels without an id in one layout and across macro calls are shown apart, the central
+/// panel in the space the others leave.
+#[test]
+fn test_panels() {
+    let ctx = egui::Context::default();
+    let mut tops = Vec::new();
+    let mut central = egui::Rect::NOTHING;
+
+    let _ = ctx.run(Default::default(), |ctx| {
+        tops.clear();
+
+        load_layout!(
+            <TopPanel>
+                tops.push(ui.max_rect());
+            </TopPanel>
+            <SidePanel side="left" default_width="100">
+                ui.label("left");
+            </SidePanel>
+        );
+
+        load_layout!(
+            <TopPanel>
+                tops.push(ui.max_rect());
+            </TopPanel>
+            <BottomPanel default_height="24">
+                ui.label("status");
+            </BottomPanel>
+            <CentralPanel>
+                central = ui.max_rect();
+            </CentralPanel>
+        );
+    });
+
+    assert_eq!(tops.len(), 2);
+    assert!(tops[0].bottom() <= tops[1].top());
+    assert!(tops[1].bottom() <= central.top());
+    assert!(central.left() >= 100.0);
+}
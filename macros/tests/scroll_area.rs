@@ -0,0 +1,34 @@
+use eframe::egui;
+use egui_xml::load_layout;
+
+/// The offset bound to a `<ScrollArea>` scrolls it and is written back after it is shown.
+#[test]
+fn test_scroll_area_offset() {
+    let ctx = egui::Context::default();
+    let mut offset = egui::vec2(0.0, 40.0);
+
+    let frame = |offset: &mut egui::Vec2| {
+        let _ = ctx.run(Default::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                load_layout!(
+                    <ScrollArea id="log" direction="vertical" max_height="100" auto_shrink="false" offset="@*offset">
+                        for line in 0..50 {
+                            ui.label(format!("line {line}"));
+                        }
+                    </ScrollArea>
+                );
+            });
+        });
+    };
+
+    frame(&mut offset);
+    frame(&mut offset);
+
+    assert_eq!(offset, egui::vec2(0.0, 40.0));
+
+    // scrolled past the end, the area clamps the offset to its content
+    offset.y = 10_000.0;
+    frame(&mut offset);
+
+    assert!(offset.y > 40.0 && offset.y < 10_000.0);
+}
@@ -0,0 +1,71 @@
+use eframe::egui;
+use egui_xml::load_layout;
+
+/// The items of a `<For>` and the condition of an `<If>` among the cells of a strip are
+/// evaluated once, though both the sizes and the contents need them.
+#[test]
+fn test_strip_evaluates_once() {
+    let ctx = egui::Context::default();
+    let mut items = 0;
+    let mut conditions = 0;
+    let mut cells = Vec::new();
+
+    let _ = ctx.run(Default::default(), |ctx| {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            load_layout!(
+                <Strip direction="east">
+                    <For each="@{ items += 1; [10.0, 20.0] }" as="width">
+                        <Panel size="exact" value="@*width">
+                            cells.push(*width);
+                        </Panel>
+                    </For>
+                    <If cond="@{ conditions += 1; true }">
+                        <For each="@{ items += 1; [30.0] }" as="width">
+                            <Panel size="exact" value="@*width">
+                                cells.push(*width);
+                            </Panel>
+                        </For>
+                    </If>
+                    <If cond="@{ conditions += 1; false }">
+                        <If cond="@{ conditions += 1; true }">
+                            <Panel size="remainder"></Panel>
+                        </If>
+                    </If>
+                </Strip>
+            );
+        });
+    });
+
+    assert_eq!((items, conditions), (2, 2));
+    // an east strip adds its cells from the last one
+    assert_eq!(cells, vec![30.0, 20.0, 10.0]);
+}
+
+/// A `<For>` among the cells of a strip binds a reference to the item in the sizes and in the
+/// contents, wherever it is.
+#[test]
+fn test_strip_binds_references() {
+    let ctx = egui::Context::default();
+    let mut cells = Vec::new();
+
+    let _ = ctx.run(Default::default(), |ctx| {
+        cells.clear();
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            load_layout!(
+                <Strip direction="north">
+                    <For each="@[10, 20]" as="row">
+                        <For each="@[1, 2]" as="column">
+                            <Panel size="exact" value="@(*row + *column) as f32">
+                                *column *= 2;
+                                cells.push(*row + *column);
+                            </Panel>
+                        </For>
+                    </For>
+                </Strip>
+            );
+        });
+    });
+
+    assert_eq!(cells, vec![12, 14, 22, 24]);
+}
@@ -0,0 +1,44 @@
+use eframe::egui;
+use egui_xml::load_layout;
+
+/// Tables without an id show their header and a row for every item in the body.
+#[test]
+fn test_table_rows() {
+    let ctx = egui::Context::default();
+    let mut cells = Vec::new();
+
+    let _ = ctx.run(Default::default(), |ctx| {
+        cells.clear();
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            load_layout!(
+                <Table striped="true">
+                    <Column size="exact" value="60"/>
+                    <Column/>
+                    <Header height="20">
+                        <Cell>cells.push("id".to_string());</Cell>
+                        <Cell>cells.push("name".to_string());</Cell>
+                    </Header>
+                    <Body row_height="18">
+                        <For each="@[1, 2]" as="number">
+                            <Row>
+                                <Cell>cells.push(number.to_string());</Cell>
+                                <Cell>ui.label("row");</Cell>
+                            </Row>
+                        </For>
+                    </Body>
+                </Table>
+                <Table>
+                    <Column/>
+                    <Body row_height="18">
+                        <Row>
+                            <Cell>cells.push("3".to_string());</Cell>
+                        </Row>
+                    </Body>
+                </Table>
+            );
+        });
+    });
+
+    assert_eq!(cells, vec!["id", "name", "1", "2", "3"]);
+}
@@ -0,0 +1,37 @@
+use eframe::egui;
+use egui_xml::load_layout;
+
+/// A `<Window>` is shown in `ctx` at the top of a layout and in the context given to it below
+/// another element, a closed one shows nothing.
+#[test]
+fn test_window_ctx() {
+    let ctx = egui::Context::default();
+    let mut open = false;
+    let mut shown = Vec::new();
+
+    let _ = ctx.run(Default::default(), |ctx| {
+        shown.clear();
+
+        load_layout!(
+            <Window title="Top" anchor="top-left">
+                shown.push("top");
+            </Window>
+            <Window title="Closed" open="@open">
+                shown.push("closed");
+            </Window>
+        );
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            load_layout!(
+                <Border inner_margin="4">
+                    <Window title="Nested" ctx="@ui.ctx()" anchor="bottom-right" anchor_offset="-10,-10">
+                        shown.push("nested");
+                    </Window>
+                </Border>
+            );
+        });
+    });
+
+    assert!(!open);
+    assert_eq!(shown, vec!["top", "nested"]);
+}
@@ -1,474 +1,895 @@
-use std::cell::RefCell;
+use std::borrow::Cow;
 use std::collections::HashMap;
-use std::rc::Rc;
-use std::str::from_utf8;
 
-use quick_xml::events::attributes::Attributes;
-use quick_xml::events::Event;
+use quick_xml::events::attributes::Attributes as XmlAttributes;
+use quick_xml::events::{BytesStart, Event};
 use quick_xml::reader::Reader;
 
-#[derive()]
+use crate::err::{Location, ParseError, Span};
+
+#[derive(Debug, Clone)]
 pub enum Node {
     Panel {
-        parent: Option<Rc<RefCell<Node>>>,
-        children: Vec<Rc<RefCell<Node>>>,
-        attributes: HashMap<String, Vec<u8>>,
+        attributes: Attributes,
     },
     Rust {
-        parent: Option<Rc<RefCell<Node>>>,
         code: String,
+        location: Location,
+        /// Byte range of the code in the source, see [`XMLForm::try_from`].
+        span: Span,
     },
     Border {
-        parent: Option<Rc<RefCell<Node>>>,
-        children: Vec<Rc<RefCell<Node>>>,
-        attributes: HashMap<String, Vec<u8>>,
+        attributes: Attributes,
     },
     Grid {
-        parent: Option<Rc<RefCell<Node>>>,
-        children: Vec<Rc<RefCell<Node>>>,
-        attributes: HashMap<String, Vec<u8>>,
+        attributes: Attributes,
     },
     Default {
-        parent: Option<Rc<RefCell<Node>>>,
-        children: Vec<Rc<RefCell<Node>>>,
-        attributes: HashMap<String, Vec<u8>>,
+        attributes: Attributes,
     },
     Strip {
-        parent: Option<Rc<RefCell<Node>>>,
-        children: Vec<Rc<RefCell<Node>>>,
-        attributes: HashMap<String, Vec<u8>>,
+        attributes: Attributes,
+    },
+    /// An element the parser does not know, kept for later passes and expanders.
+    ///
+    /// Its children are in the tree like those of every other element.
+    Element {
+        name: String,
+        attributes: Attributes,
     },
 }
 
 impl PartialEq for Node {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
-            (
-                Node::Panel {
-                    children: children1,
-                    attributes: attributes1,
-                    ..
-                },
-                Node::Panel {
-                    children: children2,
-                    attributes: attributes2,
-                    ..
-                },
-            ) => children1 == children2 && attributes1 == attributes2,
             (Node::Rust { code: code1, .. }, Node::Rust { code: code2, .. }) => code1 == code2,
-            (
-                Node::Border {
-                    children: children1,
-                    attributes: attributes1,
-                    ..
-                },
-                Node::Border {
-                    children: children2,
-                    attributes: attributes2,
-                    ..
-                },
-            ) => children1 == children2 && attributes1 == attributes2,
-            (
-                Node::Grid {
-                    children: children1,
-                    attributes: attributes1,
-                    ..
-                },
-                Node::Grid {
-                    children: children2,
-                    attributes: attributes2,
-                    ..
-                },
-            ) => children1 == children2 && attributes1 == attributes2,
-            (
-                Node::Default {
-                    children: children1,
-                    attributes: attributes1,
-                    ..
-                },
-                Node::Default {
-                    children: children2,
-                    attributes: attributes2,
-                    ..
-                },
-            ) => children1 == children2 && attributes1 == attributes2,
-            (
-                Node::Strip {
-                    children: children1,
-                    attributes: attributes1,
-                    ..
-                },
-                Node::Strip {
-                    children: children2,
-                    attributes: attributes2,
-                    ..
-                },
-            ) => children1 == children2 && attributes1 == attributes2,
-            _ => false,
+            _ => self.name() == other.name() && self.get_attributes() == other.get_attributes(),
         }
     }
 }
 
 impl Node {
-    pub fn add_node(&mut self, node: Rc<RefCell<Node>>) -> Rc<RefCell<Node>> {
-        match self {
-            Node::Panel { children, .. } => children.push(node.clone()),
-            Node::Rust { .. } => {
-                panic!("No Children!");
-            }
-            Node::Border { children, .. } => children.push(node.clone()),
-            Node::Grid { children, .. } => children.push(node.clone()),
-            Node::Default { children, .. } => children.push(node.clone()),
-            Node::Strip { children, .. } => children.push(node.clone()),
-        };
-
-        node
+    /// The node for an element `name`, [`Node::Element`] if the name is not a known element.
+    ///
+    /// A `<Rust>` element starts out without code, see [`XMLForm::append_code`].
+    pub fn from_element(name: String, attributes: Attributes) -> Node {
+        match name.as_str() {
+            "Panel" => Node::Panel { attributes },
+            "Strip" => Node::Strip { attributes },
+            "Border" => Node::Border { attributes },
+            "Grid" => Node::Grid { attributes },
+            "Rust" => Node::Rust {
+                code: "".to_string(),
+                location: attributes.location(),
+                span: Span::default(),
+            },
+            _ => Node::Element { name, attributes },
+        }
     }
 
-    pub fn get_parent(&self) -> Option<Rc<RefCell<Node>>> {
+    /// The element name this node was parsed from.
+    pub fn name(&self) -> &str {
         match self {
-            Node::Panel { parent, .. } => parent.clone(),
-            Node::Rust { parent, .. } => parent.clone(),
-            Node::Border { parent, .. } => parent.clone(),
-            Node::Grid { parent, .. } => parent.clone(),
-            Node::Default { parent, .. } => parent.clone(),
-            Node::Strip { parent, .. } => parent.clone(),
+            Node::Panel { .. } => "Panel",
+            Node::Rust { .. } => "Rust",
+            Node::Border { .. } => "Border",
+            Node::Grid { .. } => "Grid",
+            Node::Default { .. } => "Default",
+            Node::Strip { .. } => "Strip",
+            Node::Element { name, .. } => name,
         }
     }
 
-    pub fn get_children(&self) -> Option<&Vec<Rc<RefCell<Node>>>> {
+    pub fn get_attributes(&self) -> Option<&Attributes> {
         match self {
-            Node::Panel { children, .. } => Some(&children),
+            Node::Panel { attributes } => Some(attributes),
             Node::Rust { .. } => None,
-            Node::Border { children, .. } => Some(&children),
-            Node::Grid { children, .. } => Some(&children),
-            Node::Default { children, .. } => Some(&children),
-            Node::Strip { children, .. } => Some(&children),
+            Node::Border { attributes } => Some(attributes),
+            Node::Grid { attributes } => Some(attributes),
+            Node::Default { attributes } => Some(attributes),
+            Node::Strip { attributes } => Some(attributes),
+            Node::Element { attributes, .. } => Some(attributes),
         }
     }
 
-    pub fn get_attributes(&self) -> Option<&HashMap<String, Vec<u8>>> {
+    pub fn get_attributes_mut(&mut self) -> Option<&mut Attributes> {
         match self {
-            Node::Panel { attributes, .. } => Some(&attributes),
             Node::Rust { .. } => None,
-            Node::Border { attributes, .. } => Some(&attributes),
-            Node::Grid { attributes, .. } => Some(&attributes),
-            Node::Default { attributes, .. } => Some(&attributes),
-            Node::Strip { attributes, .. } => Some(&attributes),
+            Node::Panel { attributes }
+            | Node::Border { attributes }
+            | Node::Grid { attributes }
+            | Node::Default { attributes }
+            | Node::Strip { attributes }
+            | Node::Element { attributes, .. } => Some(attributes),
         }
     }
 
-    #[allow(dead_code)]
-    pub(crate) fn get_parent_mut(&mut self) -> &mut Option<Rc<RefCell<Node>>> {
+    /// Location of the element, or of the code for Rust nodes.
+    pub fn location(&self) -> Location {
         match self {
-            Node::Panel { parent, .. } => parent,
-            Node::Rust { parent, .. } => parent,
-            Node::Border { parent, .. } => parent,
-            Node::Grid { parent, .. } => parent,
-            Node::Default { parent, .. } => parent,
-            Node::Strip { parent, .. } => parent,
+            Node::Rust { location, .. } => *location,
+            _ => self
+                .get_attributes()
+                .map(Attributes::location)
+                .unwrap_or_default(),
         }
     }
 }
 
-#[derive(Debug)]
+/// Handle to a node inside an [`XMLForm`].
+///
+/// Ids are only meaningful for the form that created them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    pub(crate) fn index(&self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone)]
+struct NodeEntry {
+    node: Node,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+}
+
+/// A parsed layout, stored as an arena of nodes addressed by [`NodeId`].
+///
+/// The root is always a [`Node::Default`] holding the top-level elements of the document.
+#[derive(Clone)]
 pub struct XMLForm {
-    pub root: Rc<RefCell<Node>>,
+    nodes: Vec<NodeEntry>,
+}
+
+impl Default for XMLForm {
+    fn default() -> Self {
+        XMLForm::new()
+    }
+}
+
+impl XMLForm {
+    /// Creates a form containing only the root node.
+    pub fn new() -> Self {
+        XMLForm {
+            nodes: vec![NodeEntry {
+                node: Node::Default {
+                    attributes: Attributes::default(),
+                },
+                parent: None,
+                children: Vec::new(),
+            }],
+        }
+    }
+
+    pub fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    /// Number of nodes in the form, including the root.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// A form always contains its root, so it is never empty.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id.0].node
+    }
+
+    pub fn node_mut(&mut self, id: NodeId) -> &mut Node {
+        &mut self.nodes[id.0].node
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id.0].parent
+    }
+
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        &self.nodes[id.0].children
+    }
+
+    pub fn first_child(&self, id: NodeId) -> Option<NodeId> {
+        self.children(id).first().copied()
+    }
+
+    pub fn last_child(&self, id: NodeId) -> Option<NodeId> {
+        self.children(id).last().copied()
+    }
+
+    pub fn next_sibling(&self, id: NodeId) -> Option<NodeId> {
+        let siblings = self.children(self.parent(id)?);
+        let index = siblings.iter().position(|&sibling| sibling == id)?;
+
+        siblings.get(index + 1).copied()
+    }
+
+    pub fn previous_sibling(&self, id: NodeId) -> Option<NodeId> {
+        let siblings = self.children(self.parent(id)?);
+        let index = siblings.iter().position(|&sibling| sibling == id)?;
+
+        index.checked_sub(1).map(|index| siblings[index])
+    }
+
+    /// The parent, grandparent, ... of `id` up to and including the root.
+    pub fn ancestors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        std::iter::successors(self.parent(id), move |&id| self.parent(id))
+    }
+
+    /// All nodes below `id` in document order, not including `id` itself.
+    pub fn descendants(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let mut stack: Vec<NodeId> = self.children(id).iter().rev().copied().collect();
+
+        std::iter::from_fn(move || {
+            let next = stack.pop()?;
+            stack.extend(self.children(next).iter().rev());
+
+            Some(next)
+        })
+    }
+
+    /// Number of ancestors of `id`, the root has depth 0.
+    pub fn depth(&self, id: NodeId) -> usize {
+        self.ancestors(id).count()
+    }
+
+    /// Appends `node` as the last child of `parent` and returns its id.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `parent` is a [`Node::Rust`], which cannot have children.
+    pub fn append(&mut self, parent: NodeId, node: Node) -> NodeId {
+        if let Node::Rust { .. } = self.node(parent) {
+            panic!("No Children!");
+        }
+
+        let id = NodeId(self.nodes.len());
+
+        self.nodes.push(NodeEntry {
+            node,
+            parent: Some(parent),
+            children: Vec::new(),
+        });
+        self.nodes[parent.0].children.push(id);
+
+        id
+    }
+
+    /// Adds Rust code to `parent`.
+    ///
+    /// Code inside a `<Rust>` element extends its code and span, anywhere else it becomes a new
+    /// [`Node::Rust`].
+    pub fn append_code(&mut self, parent: NodeId, code: String, location: Location, span: Span) {
+        match self.node_mut(parent) {
+            Node::Rust {
+                code: rust_code,
+                location: rust_location,
+                span: rust_span,
+            } => {
+                if rust_code.is_empty() {
+                    *rust_location = location;
+                    *rust_span = span;
+                } else {
+                    rust_span.end = span.end;
+                }
+
+                rust_code.push_str(&code);
+            }
+            _ => {
+                self.append(
+                    parent,
+                    Node::Rust {
+                        code,
+                        location,
+                        span,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Replaces the node `id` by copies of the top-level nodes of `fragment`, at the same position.
+    ///
+    /// Returns the ids of the copies. `id` is detached: it stays valid but has no parent anymore.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is the root.
+    pub fn splice(&mut self, id: NodeId, fragment: &XMLForm) -> Vec<NodeId> {
+        let parent = self.parent(id).expect("the root cannot be replaced");
+
+        let copies: Vec<NodeId> = fragment
+            .children(fragment.root())
+            .iter()
+            .map(|&child| self.append_subtree(parent, fragment, child))
+            .collect();
+
+        // the copies were appended, move them to where `id` was
+        let children = &mut self.nodes[parent.0].children;
+        children.truncate(children.len() - copies.len());
+
+        let position = children
+            .iter()
+            .position(|&child| child == id)
+            .expect("a node is a child of its parent");
+        children.splice(position..position + 1, copies.iter().copied());
+
+        self.nodes[id.0].parent = None;
+
+        copies
+    }
+
+    /// Takes `id` and its subtree out of the tree, the handle stays valid but has no parent.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is the root.
+    pub fn detach(&mut self, id: NodeId) {
+        self.splice(id, &XMLForm::new());
+    }
+
+    /// The same form with every location pointing into source `file`, see [`Location::file`].
+    pub fn in_file(mut self, file: usize) -> Self {
+        for entry in &mut self.nodes {
+            if let Node::Rust { location, .. } = &mut entry.node {
+                location.file = file;
+            }
+
+            if let Some(attributes) = entry.node.get_attributes_mut() {
+                attributes.set_file(file);
+            }
+        }
+
+        self
+    }
+
+    /// Appends a copy of the subtree of `other_id` in `other` to `parent`.
+    pub fn append_subtree(&mut self, parent: NodeId, other: &XMLForm, other_id: NodeId) -> NodeId {
+        let id = self.append(parent, other.node(other_id).clone());
+
+        for &child in other.children(other_id) {
+            self.append_subtree(id, other, child);
+        }
+
+        id
+    }
+
+    fn subtree_eq(&self, id: NodeId, other: &XMLForm, other_id: NodeId) -> bool {
+        let children = self.children(id);
+        let other_children = other.children(other_id);
+
+        self.node(id) == other.node(other_id)
+            && children.len() == other_children.len()
+            && children
+                .iter()
+                .zip(other_children)
+                .all(|(&child, &other_child)| self.subtree_eq(child, other, other_child))
+    }
 }
 
 impl PartialEq for XMLForm {
     fn eq(&self, other: &Self) -> bool {
-        self.root == other.root
+        self.subtree_eq(self.root(), other, other.root())
     }
 }
 
 impl TryFrom<String> for XMLForm {
-    type Error = String;
+    type Error = ParseError;
 
     fn try_from(xml: String) -> Result<Self, Self::Error> {
         let mut reader = Reader::from_str(&xml);
         reader.trim_text(true);
 
-        let mut buf = Vec::new();
-
-        let root: Rc<RefCell<Node>> = Rc::new(RefCell::new(Node::Default {
-            parent: None,
-            children: Vec::new(),
-            attributes: HashMap::new(),
-        }));
-
-        let mut current_node: Rc<RefCell<Node>> = root.clone();
+        let mut form = XMLForm::new();
+        let mut current_node = form.root();
 
         loop {
-            match reader.read_event_into(&mut buf) {
-                Err(e) => panic!("Error at position {}: {:?}", reader.buffer_position(), e),
+            match reader.read_event() {
+                Err(e) => {
+                    return Err(ParseError::MalformedXml {
+                        message: e.to_string(),
+                        location: Location::from_offset(&xml, reader.buffer_position()),
+                    })
+                }
                 // exits the loop when reaching end of file
                 Ok(Event::Eof) => break,
 
                 Ok(Event::Start(region_start)) => {
-                    let attributes = prepare_attributes(region_start.attributes());
-
-                    let node = match region_start.name().as_ref() {
-                        b"Panel" => Rc::new(RefCell::new(Node::Panel {
-                            parent: Some(current_node.clone()),
-                            children: Vec::new(),
-                            attributes,
-                        })),
-                        b"Strip" => Rc::new(RefCell::new(Node::Strip {
-                            parent: Some(current_node.clone()),
-                            children: Vec::new(),
-                            attributes,
-                        })),
-                        b"Border" => Rc::new(RefCell::new(Node::Border {
-                            parent: Some(current_node.clone()),
-                            children: Vec::new(),
-                            attributes,
-                        })),
-                        b"Grid" => Rc::new(RefCell::new(Node::Grid {
-                            parent: Some(current_node.clone()),
-                            children: Vec::new(),
-                            attributes,
-                        })),
-                        b"Rust" => Rc::new(RefCell::new(Node::Rust {
-                            parent: Some(current_node.clone()),
-                            code: "".to_string(),
-                        })),
-                        _ => {
-                            panic!("Not a Node {:?}", from_utf8(region_start.name().0).unwrap())
-                        }
-                    };
-
-                    let new_node = current_node.borrow_mut().add_node(node);
-                    current_node = new_node;
+                    let node = element_node(&xml, &region_start)?;
+
+                    current_node = append_element(&mut form, current_node, node)?;
+                }
+                // a self-closing element never becomes the current node
+                Ok(Event::Empty(region_empty)) => {
+                    let node = element_node(&xml, &region_empty)?;
+
+                    append_element(&mut form, current_node, node)?;
                 }
                 Ok(Event::Text(text)) => {
-                    let text_str = from_utf8(&text).unwrap();
+                    let span = span_in(&xml, &text);
+                    let text_str = decode_entities(&String::from_utf8_lossy(&text)).to_string();
 
-                    // current_node.borrow_mut().push_text(&text);
+                    let location = Location::from_offset(&xml, span.start);
 
-                    current_node
-                        .borrow_mut()
-                        .add_node(Rc::new(RefCell::new(Node::Rust {
-                            parent: Some(current_node.clone()),
-                            code: text_str.to_string(),
-                        })));
+                    form.append_code(current_node, text_str, location, span);
                 }
-                Ok(Event::End(_)) => {
-                    let parent = current_node.borrow_mut().get_parent();
+                // CDATA sections are taken verbatim, so Rust can use `<` and `&` freely
+                Ok(Event::CData(cdata)) => {
+                    let span = span_in(&xml, &cdata);
+                    let code = String::from_utf8_lossy(&cdata).to_string();
 
-                    if parent.is_some() {
-                        current_node = parent.unwrap();
+                    let location = Location::from_offset(&xml, span.start);
+
+                    form.append_code(current_node, code, location, span);
+                }
+                Ok(Event::End(_)) => {
+                    if let Some(parent) = form.parent(current_node) {
+                        current_node = parent;
                     }
                 }
-                _ => (),
+                // comments, processing instructions, the declaration and doctypes carry no layout
+                Ok(Event::Comment(_) | Event::PI(_) | Event::Decl(_) | Event::DocType(_)) => (),
             }
-
-            buf.clear();
         }
 
-        Ok(XMLForm { root })
+        Ok(form)
     }
 }
 
-fn prepare_attributes(attributes: Attributes) -> HashMap<String, Vec<u8>> {
-    let mut map = HashMap::new();
+fn element_node(source: &str, start: &BytesStart) -> Result<Node, ParseError> {
+    let element = String::from_utf8_lossy(start.name().as_ref()).to_string();
 
-    attributes.for_each(|a| {
-        let a = a.unwrap();
+    // the tag content starts right after the opening `<`
+    let location = Location::from_offset(source, offset_in(source, start) - 1);
 
-        map.insert(
-            from_utf8(a.key.0).unwrap().to_string(),
-            a.value.iter().map(|&x| x).collect::<Vec<u8>>(),
-        );
-    });
+    let attributes = prepare_attributes(source, &element, location, start.attributes())?;
 
-    map
+    Ok(Node::from_element(element, attributes))
 }
 
-pub mod attribute {
-    use quote::quote;
-    use std::{collections::HashMap, str::FromStr};
+/// Appends an element to `parent`, failing if `parent` is a `<Rust>` element.
+fn append_element(form: &mut XMLForm, parent: NodeId, node: Node) -> Result<NodeId, ParseError> {
+    if let Node::Rust { .. } = form.node(parent) {
+        return Err(ParseError::UnexpectedElement {
+            element: node.name().to_string(),
+            parent: "Rust".to_string(),
+            location: node.location(),
+        });
+    }
 
-    #[derive(Clone)]
-    pub enum HybridAttribute<T: Clone> {
-        Literal(T),
-        DynamicRust(proc_macro2::TokenStream),
+    Ok(form.append(parent, node))
+}
+
+/// Replaces the predefined XML entities and character references.
+///
+/// Unlike strict XML a `&` that does not start an entity is kept as it is,
+/// so Rust like `&mut self.value` can be written without escaping.
+pub(crate) fn decode_entities(text: &str) -> Cow<'_, str> {
+    if !text.contains('&') {
+        return Cow::Borrowed(text);
     }
 
-    impl<T: Into<proc_macro2::TokenStream> + Clone> Into<proc_macro2::TokenStream>
-        for HybridAttribute<T>
-    {
-        fn into(self) -> proc_macro2::TokenStream {
-            match self {
-                HybridAttribute::Literal(value) => value.into(),
-                HybridAttribute::DynamicRust(stream) => stream,
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest[1..]
+            .find(';')
+            .map(|end| &rest[1..end + 1])
+            .and_then(|name| Some((name, decode_entity(name)?)));
+
+        match entity {
+            Some((name, character)) => {
+                decoded.push(character);
+                rest = &rest[name.len() + 2..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
             }
         }
     }
 
-    pub fn parse_rust_attribute(
-        attributes: &HashMap<String, Vec<u8>>,
-        attribute: &str,
-    ) -> Result<proc_macro2::TokenStream, String> {
-        let code = match attributes.get(attribute) {
-            Some(code) => code,
-            None => return Err(format!("Attribute {} not found", attribute)),
-        };
+    decoded.push_str(rest);
 
-        if code.is_empty() || code[0] != b'@' {
-            return Err("Code must start with @".to_string());
+    Cow::Owned(decoded)
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    match name {
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "amp" => Some('&'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        _ => {
+            let code = match name.strip_prefix("#x") {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => name.strip_prefix('#')?.parse().ok()?,
+            };
+
+            char::from_u32(code)
         }
+    }
+}
 
-        let code = match std::str::from_utf8(&code[1..]) {
-            Ok(code) => code,
-            Err(_) => return Err("Failed to parse code".to_string()),
-        };
+/// Byte offset of a slice borrowed from `source` (events of a `&str` reader borrow the input).
+fn offset_in(source: &str, slice: &[u8]) -> usize {
+    (slice.as_ptr() as usize).saturating_sub(source.as_ptr() as usize)
+}
 
-        let stream = match code.parse() {
-            Ok(stream) => stream,
-            Err(_) => return Err("Failed to parse code".to_string()),
+fn span_in(source: &str, slice: &[u8]) -> Span {
+    let start = offset_in(source, slice);
+
+    Span::new(start, start + slice.len())
+}
+
+fn prepare_attributes(
+    source: &str,
+    element: &str,
+    location: Location,
+    attributes: XmlAttributes,
+) -> Result<Attributes, ParseError> {
+    let mut prepared = Attributes::new(element, location);
+
+    for attribute in attributes {
+        let attribute = attribute.map_err(|e| ParseError::MalformedXml {
+            message: e.to_string(),
+            location,
+        })?;
+
+        let key = attribute.key.as_ref();
+
+        // the raw value is borrowed from the source, its span excludes the quotes
+        let span = match &attribute.value {
+            Cow::Borrowed(value) => span_in(source, value),
+            Cow::Owned(_) => span_in(source, key),
         };
 
-        Ok(stream)
+        prepared.insert(
+            String::from_utf8_lossy(key).to_string(),
+            decode_entities(&String::from_utf8_lossy(&attribute.value)).to_string(),
+            Location::from_offset(source, offset_in(source, key)),
+            span,
+        );
     }
 
-    pub fn parse_optional_rust_attribute(
-        attributes: &HashMap<String, Vec<u8>>,
-        attribute: &str,
-    ) -> Result<Option<proc_macro2::TokenStream>, String> {
-        let code = match attributes.get(attribute) {
-            Some(code) => code,
-            None => return Ok(None),
-        };
+    Ok(prepared)
+}
 
-        if code.is_empty() {
-            return Ok(None);
+/// The attributes of an element together with where they were written.
+///
+/// Equality only looks at names and values, so trees built by hand compare equal to parsed ones.
+#[derive(Clone, Default)]
+pub struct Attributes {
+    element: String,
+    location: Location,
+    values: HashMap<String, AttributeEntry>,
+}
+
+#[derive(Clone)]
+struct AttributeEntry {
+    value: String,
+    location: Location,
+    span: Span,
+}
+
+impl Attributes {
+    pub fn new(element: &str, location: Location) -> Self {
+        Attributes {
+            element: element.to_string(),
+            location,
+            values: HashMap::new(),
         }
+    }
+
+    /// Sets `name` to `value`, `location` points at the name and `span` covers the raw value.
+    pub fn insert(&mut self, name: String, value: String, location: Location, span: Span) {
+        self.values.insert(
+            name,
+            AttributeEntry {
+                value,
+                location,
+                span,
+            },
+        );
+    }
 
-        if code[0] != b'@' {
-            return Err("Code must start with @".to_string());
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(|entry| entry.value.as_str())
+    }
+
+    fn set_file(&mut self, file: usize) {
+        self.location.file = file;
+
+        for entry in self.values.values_mut() {
+            entry.location.file = file;
         }
+    }
 
-        let code = match std::str::from_utf8(&code[1..]) {
-            Ok(code) => code,
-            Err(_) => return Err("Failed to parse attribute".to_string()),
-        };
+    /// The values by name, for passes that rewrite them in place.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&str, &mut String)> {
+        self.values
+            .iter_mut()
+            .map(|(name, entry)| (name.as_str(), &mut entry.value))
+    }
 
-        let stream = match code.parse() {
-            Ok(stream) => stream,
-            Err(_) => return Err("Failed to parse attribute".to_string()),
-        };
+    /// Byte range of the value of `name` in the source, without the quotes.
+    pub fn span_of(&self, name: &str) -> Option<Span> {
+        self.values.get(name).map(|entry| entry.span)
+    }
 
-        Ok(Some(stream))
+    /// Name of the element these attributes belong to.
+    pub fn element(&self) -> &str {
+        &self.element
     }
 
-    pub fn parse_optional_hybrid_attribute<T: FromStr + Into<proc_macro2::TokenStream> + Clone>(
-        attributes: &HashMap<String, Vec<u8>>,
-        attribute: &str,
-    ) -> Result<Option<HybridAttribute<T>>, String> {
-        let code = match attributes.get(attribute) {
-            Some(code) => code,
-            None => return Ok(None),
-        };
+    /// Location of the element these attributes belong to.
+    pub fn location(&self) -> Location {
+        self.location
+    }
 
-        if code.is_empty() {
-            return Ok(None);
+    /// Location of the attribute `name`, falling back to the element if it is missing.
+    pub fn location_of(&self, name: &str) -> Location {
+        self.values
+            .get(name)
+            .map(|entry| entry.location)
+            .unwrap_or(self.location)
+    }
+
+    /// Fails with [`ParseError::UnknownAttribute`] for the first attribute `known` rejects.
+    pub fn check(&self, known: impl Fn(&str) -> bool) -> Result<(), ParseError> {
+        let unknown = self
+            .values
+            .keys()
+            .filter(|name| !known(name))
+            .min_by_key(|name| self.location_of(name));
+
+        match unknown {
+            Some(name) => Err(ParseError::UnknownAttribute {
+                element: self.element.clone(),
+                attribute: name.clone(),
+                location: self.location_of(name),
+            }),
+            None => Ok(()),
         }
+    }
 
-        if code[0] != b'@' {
-            let code = match std::str::from_utf8(code) {
-                Ok(code) => code,
-                Err(_) => return Err("Failed to parse attribute".to_string()),
-            };
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.values
+            .iter()
+            .map(|(name, entry)| (name.as_str(), entry.value.as_str()))
+    }
 
-            let value = match code.parse::<T>() {
-                Ok(value) => value,
-                Err(_) => return Err("Failed to parse attribute".to_string()),
-            };
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl std::fmt::Debug for Attributes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
 
-            return Ok(Some(HybridAttribute::Literal(value)));
+impl PartialEq for Attributes {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(name, value)| other.get(name) == Some(value))
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for Attributes {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut attributes = Attributes::default();
+
+        for (name, value) in iter {
+            attributes.insert(
+                name.into(),
+                value.into(),
+                Location::default(),
+                Span::default(),
+            );
         }
 
-        let code = match std::str::from_utf8(&code[1..]) {
-            Ok(code) => code,
-            Err(_) => return Err("Failed to parse attribute".to_string()),
-        };
+        attributes
+    }
+}
 
-        match code.parse::<T>() {
-            Ok(_) => return Err("Unneeded @ to indicate Rust Code".to_string()),
-            Err(_) => (),
-        };
+pub mod attribute {
+    use quote::quote;
+    use std::str::FromStr;
+
+    use super::Attributes;
+    use crate::err::ParseError;
+
+    #[derive(Clone)]
+    pub enum HybridAttribute<T: Clone> {
+        Literal(T),
+        DynamicRust(proc_macro2::TokenStream),
+    }
 
-        let stream = match code.parse() {
-            Ok(stream) => stream,
-            Err(_) => {
-                return Err("Failed to parse code".to_string());
+    impl<T: Into<proc_macro2::TokenStream> + Clone> From<HybridAttribute<T>>
+        for proc_macro2::TokenStream
+    {
+        fn from(attribute: HybridAttribute<T>) -> Self {
+            match attribute {
+                HybridAttribute::Literal(value) => value.into(),
+                HybridAttribute::DynamicRust(stream) => stream,
             }
-        };
+        }
+    }
 
-        Ok(Some(HybridAttribute::DynamicRust(stream)))
+    fn missing_attribute(attributes: &Attributes, attribute: &str) -> ParseError {
+        ParseError::MissingAttribute {
+            element: attributes.element().to_string(),
+            attribute: attribute.to_string(),
+            location: attributes.location(),
+        }
     }
 
-    pub fn parse_hybrid_attribute<T: FromStr + Into<proc_macro2::TokenStream> + Clone>(
-        attributes: &HashMap<String, Vec<u8>>,
+    fn invalid_literal(attributes: &Attributes, attribute: &str, value: &str) -> ParseError {
+        ParseError::InvalidLiteral {
+            element: attributes.element().to_string(),
+            attribute: attribute.to_string(),
+            value: value.to_string(),
+            location: attributes.location_of(attribute),
+        }
+    }
+
+    fn parse_code(
+        attributes: &Attributes,
+        attribute: &str,
+        code: &str,
+    ) -> Result<proc_macro2::TokenStream, ParseError> {
+        code.parse().map_err(|_| ParseError::InvalidRust {
+            element: attributes.element().to_string(),
+            attribute: Some(attribute.to_string()),
+            code: code.to_string(),
+            location: attributes.location_of(attribute),
+        })
+    }
+
+    fn missing_rust_prefix(attributes: &Attributes, attribute: &str) -> ParseError {
+        ParseError::MissingRustPrefix {
+            element: attributes.element().to_string(),
+            attribute: attribute.to_string(),
+            location: attributes.location_of(attribute),
+        }
+    }
+
+    pub fn parse_rust_attribute(
+        attributes: &Attributes,
         attribute: &str,
-    ) -> Result<HybridAttribute<T>, String> {
+    ) -> Result<proc_macro2::TokenStream, ParseError> {
         let code = match attributes.get(attribute) {
             Some(code) => code,
-            None => return Err(format!("Attribute {} not found", attribute)),
+            None => return Err(missing_attribute(attributes, attribute)),
         };
 
-        if code.is_empty() {
-            return Err("Attribute is empty".to_string());
+        match code.strip_prefix('@') {
+            Some(code) => parse_code(attributes, attribute, code),
+            None => Err(missing_rust_prefix(attributes, attribute)),
         }
+    }
 
-        if code[0] != b'@' {
-            let code = match std::str::from_utf8(code) {
-                Ok(code) => code,
-                Err(_) => return Err("Failed to parse attribute".to_string()),
-            };
-
-            let value = match code.parse::<T>() {
-                Ok(value) => value,
-                Err(_) => return Err("Failed to parse attribute".to_string()),
-            };
+    pub fn parse_optional_rust_attribute(
+        attributes: &Attributes,
+        attribute: &str,
+    ) -> Result<Option<proc_macro2::TokenStream>, ParseError> {
+        match attributes.get(attribute) {
+            Some(code) if !code.is_empty() => parse_rust_attribute(attributes, attribute).map(Some),
+            _ => Ok(None),
+        }
+    }
 
-            return Ok(HybridAttribute::Literal(value));
+    pub fn parse_optional_hybrid_attribute<T: FromStr + Into<proc_macro2::TokenStream> + Clone>(
+        attributes: &Attributes,
+        attribute: &str,
+    ) -> Result<Option<HybridAttribute<T>>, ParseError> {
+        match attributes.get(attribute) {
+            Some(code) if !code.is_empty() => {
+                parse_hybrid_attribute(attributes, attribute).map(Some)
+            }
+            _ => Ok(None),
         }
+    }
 
-        let code = match std::str::from_utf8(&code[1..]) {
-            Ok(code) => code,
-            Err(_) => return Err("Failed to parse code".to_string()),
+    pub fn parse_hybrid_attribute<T: FromStr + Into<proc_macro2::TokenStream> + Clone>(
+        attributes: &Attributes,
+        attribute: &str,
+    ) -> Result<HybridAttribute<T>, ParseError> {
+        let code = match attributes.get(attribute) {
+            Some(code) => code,
+            None => return Err(missing_attribute(attributes, attribute)),
         };
 
-        match code.parse::<T>() {
-            Ok(_) => return Err("Unneeded @ to indicate Rust Code".to_string()),
-            Err(_) => (),
+        let code = match code.strip_prefix('@') {
+            Some(code) => code,
+            None => {
+                return match code.parse::<T>() {
+                    Ok(value) => Ok(HybridAttribute::Literal(value)),
+                    Err(_) => Err(invalid_literal(attributes, attribute, code)),
+                }
+            }
         };
 
-        let stream = match code.parse() {
-            Ok(stream) => stream,
-            Err(_) => return Err("Failed to parse code".to_string()),
-        };
+        if code.parse::<T>().is_ok() {
+            return Err(ParseError::UnneededRustPrefix {
+                element: attributes.element().to_string(),
+                attribute: attribute.to_string(),
+                location: attributes.location_of(attribute),
+            });
+        }
 
-        Ok(HybridAttribute::DynamicRust(stream))
+        Ok(HybridAttribute::DynamicRust(parse_code(
+            attributes, attribute, code,
+        )?))
     }
 
-    pub fn parse_string(
-        attributes: &HashMap<String, Vec<u8>>,
+    /// Parses an attribute that is Rust code without the `@`, like the binding of a loop.
+    pub fn parse_code_attribute(
+        attributes: &Attributes,
         attribute: &str,
-    ) -> Result<String, String> {
-        let code = match attributes.get(attribute) {
-            Some(code) => code,
-            None => return Err(format!("Attribute {} not found", attribute)),
-        };
+    ) -> Result<proc_macro2::TokenStream, ParseError> {
+        let code = parse_string(attributes, attribute)?;
 
-        let code = match std::str::from_utf8(code) {
-            Ok(code) => code,
-            Err(_) => return Err("Failed to parse code".to_string()),
-        };
+        parse_code(attributes, attribute, &code)
+    }
+
+    /// Parses a text attribute into a string literal, or Rust code if it starts with `@`.
+    pub fn parse_optional_text_attribute(
+        attributes: &Attributes,
+        attribute: &str,
+    ) -> Result<Option<proc_macro2::TokenStream>, ParseError> {
+        match attributes.get(attribute) {
+            Some(code) if code.starts_with('@') => {
+                parse_rust_attribute(attributes, attribute).map(Some)
+            }
+            Some(text) => Ok(Some(AttributeString(text.to_string()).into())),
+            None => Ok(None),
+        }
+    }
 
-        Ok(code.to_string())
+    /// Like [`parse_optional_text_attribute`], for a required attribute.
+    pub fn parse_text_attribute(
+        attributes: &Attributes,
+        attribute: &str,
+    ) -> Result<proc_macro2::TokenStream, ParseError> {
+        parse_optional_text_attribute(attributes, attribute)?
+            .ok_or_else(|| missing_attribute(attributes, attribute))
+    }
+
+    pub fn parse_string(attributes: &Attributes, attribute: &str) -> Result<String, ParseError> {
+        match attributes.get(attribute) {
+            Some(value) => Ok(value.to_string()),
+            None => Err(missing_attribute(attributes, attribute)),
+        }
+    }
+
+    /// Parses a plain (non-`@`) attribute with `FromStr`, e.g. an enum of allowed keywords.
+    pub fn parse_literal<T: FromStr>(
+        attributes: &Attributes,
+        attribute: &str,
+    ) -> Result<T, ParseError> {
+        let value = parse_string(attributes, attribute)?;
+
+        value
+            .parse()
+            .map_err(|_| invalid_literal(attributes, attribute, &value))
     }
 
     #[derive(Clone)]
@@ -485,9 +906,9 @@ pub mod attribute {
         }
     }
 
-    impl Into<proc_macro2::TokenStream> for AttributeF32 {
-        fn into(self) -> proc_macro2::TokenStream {
-            let literal = proc_macro2::Literal::f32_unsuffixed(self.0);
+    impl From<AttributeF32> for proc_macro2::TokenStream {
+        fn from(attribute: AttributeF32) -> Self {
+            let literal = proc_macro2::Literal::f32_unsuffixed(attribute.0);
 
             quote! { #literal }
         }
@@ -507,9 +928,9 @@ pub mod attribute {
         }
     }
 
-    impl Into<proc_macro2::TokenStream> for AttributeU32 {
-        fn into(self) -> proc_macro2::TokenStream {
-            let literal = proc_macro2::Literal::u32_unsuffixed(self.0);
+    impl From<AttributeU32> for proc_macro2::TokenStream {
+        fn from(attribute: AttributeU32) -> Self {
+            let literal = proc_macro2::Literal::u32_unsuffixed(attribute.0);
 
             quote! { #literal }
         }
@@ -526,12 +947,11 @@ pub mod attribute {
         }
     }
 
-    impl Into<proc_macro2::TokenStream> for AttributeString {
-        fn into(self) -> proc_macro2::TokenStream {
-            match self.0.parse() {
-                Ok(stream) => stream,
-                Err(_) => panic!("Failed to parse string"),
-            }
+    impl From<AttributeString> for proc_macro2::TokenStream {
+        fn from(attribute: AttributeString) -> Self {
+            let literal = proc_macro2::Literal::string(&attribute.0);
+
+            quote! { #literal }
         }
     }
 
@@ -549,9 +969,9 @@ pub mod attribute {
         }
     }
 
-    impl Into<proc_macro2::TokenStream> for AttributeBool {
-        fn into(self) -> proc_macro2::TokenStream {
-            if self.0 {
+    impl From<AttributeBool> for proc_macro2::TokenStream {
+        fn from(attribute: AttributeBool) -> Self {
+            if attribute.0 {
                 quote! { true }
             } else {
                 quote! { false }
@@ -559,6 +979,131 @@ pub mod attribute {
         }
     }
 
+    /// Two floats written `x,y`, a single one is used for both.
+    #[derive(Clone)]
+    pub struct AttributeVec2(pub f32, pub f32);
+
+    impl FromStr for AttributeVec2 {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let parse = |value: &str| {
+                value
+                    .trim()
+                    .parse::<f32>()
+                    .map_err(|_| "Failed to parse attribute".to_string())
+            };
+
+            match s.split_once(',') {
+                Some((x, y)) => Ok(AttributeVec2(parse(x)?, parse(y)?)),
+                None => parse(s).map(|value| AttributeVec2(value, value)),
+            }
+        }
+    }
+
+    impl From<AttributeVec2> for proc_macro2::TokenStream {
+        fn from(attribute: AttributeVec2) -> Self {
+            let x = proc_macro2::Literal::f32_unsuffixed(attribute.0);
+            let y = proc_macro2::Literal::f32_unsuffixed(attribute.1);
+
+            quote! { egui::vec2(#x, #y) }
+        }
+    }
+
+    /// A color written `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`.
+    #[derive(Clone)]
+    pub struct AttributeColor(pub [u8; 4]);
+
+    impl FromStr for AttributeColor {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let error = || "Failed to parse attribute".to_string();
+
+            let hex = s.strip_prefix('#').ok_or_else(error)?;
+
+            if !hex.is_ascii() {
+                return Err(error());
+            }
+
+            let digits: Vec<u8> = match hex.len() {
+                // every digit stands for a whole byte, `#4` is `#44`
+                3 | 4 => hex
+                    .chars()
+                    .map(|digit| u8::from_str_radix(&digit.to_string().repeat(2), 16))
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| error())?,
+                6 | 8 => (0..hex.len())
+                    .step_by(2)
+                    .map(|index| u8::from_str_radix(&hex[index..index + 2], 16))
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| error())?,
+                _ => return Err(error()),
+            };
+
+            match digits[..] {
+                [r, g, b] => Ok(AttributeColor([r, g, b, 255])),
+                [r, g, b, a] => Ok(AttributeColor([r, g, b, a])),
+                _ => Err(error()),
+            }
+        }
+    }
+
+    impl From<AttributeColor> for proc_macro2::TokenStream {
+        fn from(attribute: AttributeColor) -> Self {
+            let [r, g, b, a] = attribute.0.map(proc_macro2::Literal::u8_unsuffixed);
+
+            quote! { egui::Color32::from_rgba_unmultiplied(#r, #g, #b, #a) }
+        }
+    }
+
+    /// A field path like `customer.name` or `0`, read from a value the macro binds.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct AttributeField(pub Vec<String>);
+
+    impl FromStr for AttributeField {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let is_segment = |segment: &str| match segment.chars().next() {
+                Some(first) if first.is_ascii_digit() => {
+                    segment.chars().all(|c| c.is_ascii_digit())
+                }
+                Some(first) if first.is_alphabetic() || first == '_' => {
+                    segment.chars().all(|c| c.is_alphanumeric() || c == '_') && segment != "_"
+                }
+                _ => false,
+            };
+
+            let segments: Vec<String> = s
+                .split('.')
+                .map(|segment| segment.trim().to_string())
+                .collect();
+
+            match segments.iter().all(|segment| is_segment(segment)) {
+                true => Ok(AttributeField(segments)),
+                false => Err("Failed to parse attribute".to_string()),
+            }
+        }
+    }
+
+    /// The accessors of the path, `.customer.name`, to put after the bound value.
+    impl From<AttributeField> for proc_macro2::TokenStream {
+        fn from(attribute: AttributeField) -> Self {
+            attribute
+                .0
+                .iter()
+                .map(|segment| {
+                    let segment: proc_macro2::TokenStream = segment
+                        .parse()
+                        .expect("a field is an identifier or an index");
+
+                    quote! { .#segment }
+                })
+                .collect()
+        }
+    }
+
     #[derive(Clone)]
     pub struct AttributeSizeType(pub String);
 }
@@ -566,10 +1111,8 @@ pub mod attribute {
 mod test {
     #[test]
     fn test() {
-        use super::{Node, XMLForm};
-        use std::cell::RefCell;
-        use std::collections::HashMap;
-        use std::rc::Rc;
+        use super::{Attributes, Node, XMLForm};
+        use crate::err::{Location, Span};
 
         let xml = r#"
         <Form>
@@ -593,96 +1136,345 @@ mod test {
 
         let form = XMLForm::try_from(xml.to_string()).unwrap();
 
-        let root = Rc::new(RefCell::new(Node::Default {
-            parent: None,
-            children: vec![],
-            attributes: HashMap::new(),
-        }));
-
-        let strip = Rc::new(RefCell::new(Node::Strip {
-            parent: None,
-            children: vec![
-                Rc::new(RefCell::new(Node::Panel {
-                    parent: None,
-                    children: vec![Rc::new(RefCell::new(Node::Strip {
-                        parent: None,
-                        children: vec![
-                            Rc::new(RefCell::new(Node::Panel {
-                                parent: None,
-                                children: vec![Rc::new(RefCell::new(Node::Rust {
-                                    parent: None,
-                                    code: "if ui.button(\"Hi I am a button!\").clicked() {println!(\"Button clicked!\");}"
-                                        .to_string(),
-                                }))],
-                                attributes: {
-                                    let mut map = HashMap::new();
-                                    map.insert("size".to_string(), "exact".as_bytes().to_vec());
-                                    map.insert("value".to_string(), "250.0".as_bytes().to_vec());
-                                    map
-                                },
-                            })),
-                            Rc::new(RefCell::new(Node::Panel {
-                                parent: None,
-                                children: vec![Rc::new(RefCell::new(Node::Rust {
-                                    parent: None,
-                                    code: "ui.label(\"Hello from XML!\");".to_string(),
-                                }))],
-                                attributes: {
-                                    let mut map = HashMap::new();
-                                    map.insert("size".to_string(), "remainder".as_bytes().to_vec());
-                                    map
-                                },
-                            })),
-                        ],
-                        attributes: {
-                            let mut map = HashMap::new();
-                            map.insert("direction".to_string(), "east".as_bytes().to_vec());
-                            map
-                        },
-                    }))],
-                    attributes: {
-                        let mut map = HashMap::new();
-                        map.insert("size".to_string(), "relative".as_bytes().to_vec());
-                        map.insert("value".to_string(), "0.4".as_bytes().to_vec());
-                        map
-                    },
-                })),
-                Rc::new(RefCell::new(Node::Panel {
-                    parent: None,
-                    children: vec![Rc::new(RefCell::new(Node::Rust {
-                        parent: None,
-                        code: "ui.label(\"Hello from XML!\");".to_string(),
-                    }))],
-                    attributes: {
-                        let mut map = HashMap::new();
-                        map.insert("size".to_string(), "remainder".as_bytes().to_vec());
-                        map
-                    },
-                })),
-            ],
-            attributes: {
-                let mut map = HashMap::new();
-                map.insert("direction".to_string(), "south".as_bytes().to_vec());
-                map
+        let mut eq_form = XMLForm::new();
+
+        let form_element = eq_form.append(
+            eq_form.root(),
+            Node::Element {
+                name: "Form".to_string(),
+                attributes: Attributes::new("Form", Location::default()),
+            },
+        );
+
+        let strip = eq_form.append(
+            form_element,
+            Node::Strip {
+                attributes: Attributes::from_iter([("direction", "south")]),
             },
-        }));
+        );
 
-        fn set_parent_recursive(node: Rc<RefCell<Node>>, parent: Option<Rc<RefCell<Node>>>) {
-            *node.borrow_mut().get_parent_mut() = parent;
+        let panel = eq_form.append(
+            strip,
+            Node::Panel {
+                attributes: Attributes::from_iter([("size", "relative"), ("value", "0.4")]),
+            },
+        );
 
-            if let Some(children) = node.borrow_mut().get_children() {
-                children.iter().for_each(|child| {
-                    set_parent_recursive(child.clone(), Some(node.clone()));
-                });
-            }
-        }
+        let inner_strip = eq_form.append(
+            panel,
+            Node::Strip {
+                attributes: Attributes::from_iter([("direction", "east")]),
+            },
+        );
+
+        let inner_panel = eq_form.append(
+            inner_strip,
+            Node::Panel {
+                attributes: Attributes::from_iter([("size", "exact"), ("value", "250.0")]),
+            },
+        );
+
+        eq_form.append(
+            inner_panel,
+            Node::Rust {
+                code:
+                    "if ui.button(\"Hi I am a button!\").clicked() {println!(\"Button clicked!\");}"
+                        .to_string(),
+                location: Location::default(),
+                span: Span::default(),
+            },
+        );
+
+        let inner_panel = eq_form.append(
+            inner_strip,
+            Node::Panel {
+                attributes: Attributes::from_iter([("size", "remainder")]),
+            },
+        );
+
+        eq_form.append(
+            inner_panel,
+            Node::Rust {
+                code: "ui.label(\"Hello from XML!\");".to_string(),
+                location: Location::default(),
+                span: Span::default(),
+            },
+        );
+
+        let panel = eq_form.append(
+            strip,
+            Node::Panel {
+                attributes: Attributes::from_iter([("size", "remainder")]),
+            },
+        );
+
+        eq_form.append(
+            panel,
+            Node::Rust {
+                code: "ui.label(\"Hello from XML!\");".to_string(),
+                location: Location::default(),
+                span: Span::default(),
+            },
+        );
+
+        assert_eq!(form, eq_form);
+    }
+
+    #[test]
+    fn test_errors() {
+        use super::attribute::{parse_hybrid_attribute, AttributeF32};
+        use super::XMLForm;
+        use crate::err::{Location, ParseError};
+
+        let xml =
+            "<Strip direction=\"north\">\n    <Panel size=\"remainder\"/>\n    <Table/>\n</Strip>";
+
+        let form = XMLForm::try_from(xml.to_string()).unwrap();
+        let table = form
+            .last_child(form.first_child(form.root()).unwrap())
+            .unwrap();
+
+        assert_eq!(form.node(table).name(), "Table");
+        assert_eq!(form.node(table).location(), Location::new(3, 5));
+
+        let xml = "<Strip direction=\"north\">\n    <Panel size=\"exact\"\n        value=\"25O\">\n    </Panel>\n</Strip>";
+        let form = XMLForm::try_from(xml.to_string()).unwrap();
+
+        let strip = form.first_child(form.root()).unwrap();
+        let panel = form.first_child(strip).unwrap();
+        let attributes = form.node(panel).get_attributes().unwrap();
+
+        assert_eq!(
+            parse_hybrid_attribute::<AttributeF32>(attributes, "value").err(),
+            Some(ParseError::InvalidLiteral {
+                element: "Panel".to_string(),
+                attribute: "value".to_string(),
+                value: "25O".to_string(),
+                location: Location::new(3, 9),
+            })
+        );
+
+        assert_eq!(
+            parse_hybrid_attribute::<AttributeF32>(attributes, "min").err(),
+            Some(ParseError::MissingAttribute {
+                element: "Panel".to_string(),
+                attribute: "min".to_string(),
+                location: Location::new(2, 5),
+            })
+        );
+
+        let xml = "<Rust>\n    <Panel/>\n</Rust>";
+
+        assert_eq!(
+            XMLForm::try_from(xml.to_string()).err(),
+            Some(ParseError::UnexpectedElement {
+                element: "Panel".to_string(),
+                parent: "Rust".to_string(),
+                location: Location::new(2, 5),
+            })
+        );
+
+        let xml = "<Rust>ui.label(\"a\");<Strip direction=\"north\"></Strip></Rust>";
+
+        assert_eq!(
+            XMLForm::try_from(xml.to_string()).err(),
+            Some(ParseError::UnexpectedElement {
+                element: "Strip".to_string(),
+                parent: "Rust".to_string(),
+                location: Location::new(1, 21),
+            })
+        );
+    }
+
+    #[test]
+    fn test_navigation() {
+        use super::XMLForm;
+
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<XMLForm>();
+
+        let xml = r#"
+        <Strip direction="north">
+            <Panel size="remainder">ui.label("first");</Panel>
+            <Panel size="remainder">ui.label("second");</Panel>
+        </Strip>
+        "#;
+
+        let form = XMLForm::try_from(xml.to_string()).unwrap();
+
+        let strip = form.first_child(form.root()).unwrap();
+        let first = form.first_child(strip).unwrap();
+        let second = form.last_child(strip).unwrap();
+
+        assert_eq!(form.parent(strip), Some(form.root()));
+        assert_eq!(form.next_sibling(first), Some(second));
+        assert_eq!(form.previous_sibling(second), Some(first));
+        assert_eq!(form.next_sibling(second), None);
+        assert_eq!(form.previous_sibling(first), None);
+
+        let code = form.first_child(second).unwrap();
 
-        set_parent_recursive(strip.clone(), None);
+        assert_eq!(form.depth(code), 3);
+        assert_eq!(
+            form.ancestors(code).collect::<Vec<_>>(),
+            vec![second, strip, form.root()]
+        );
+        assert_eq!(
+            form.descendants(form.root())
+                .map(|id| form.node(id).name())
+                .collect::<Vec<_>>(),
+            vec!["Strip", "Panel", "Rust", "Panel", "Rust"]
+        );
+    }
 
-        root.borrow_mut().add_node(strip);
+    #[test]
+    fn test_conformance() {
+        use super::{Attributes, Node, XMLForm};
+        use crate::err::{Location, Span};
+
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+        <!-- the whole window -->
+        <Strip direction="north" gap="@ui.spacing().item_spacing.x &lt; 4.0">
+            <?egui_xml ignored?>
+            <Panel size="remainder"/>
+            <Panel size="exact" value="20">
+                if a &lt; b &amp;&amp; ready { ui.label("&#x3C;&#62;"); }
+                <![CDATA[ui.add(egui::Slider::new(&mut self.value, 0..=10));]]>
+            </Panel>
+        </Strip>
+        "#;
+
+        let form = XMLForm::try_from(xml.to_string()).unwrap();
 
-        let eq_form = XMLForm { root };
+        let mut eq_form = XMLForm::new();
+
+        let strip = eq_form.append(
+            eq_form.root(),
+            Node::Strip {
+                attributes: Attributes::from_iter([
+                    ("direction", "north"),
+                    ("gap", "@ui.spacing().item_spacing.x < 4.0"),
+                ]),
+            },
+        );
+
+        eq_form.append(
+            strip,
+            Node::Panel {
+                attributes: Attributes::from_iter([("size", "remainder")]),
+            },
+        );
+
+        let panel = eq_form.append(
+            strip,
+            Node::Panel {
+                attributes: Attributes::from_iter([("size", "exact"), ("value", "20")]),
+            },
+        );
+
+        eq_form.append(
+            panel,
+            Node::Rust {
+                code: "if a < b && ready { ui.label(\"<>\"); }".to_string(),
+                location: Location::default(),
+                span: Span::default(),
+            },
+        );
+
+        eq_form.append(
+            panel,
+            Node::Rust {
+                code: "ui.add(egui::Slider::new(&mut self.value, 0..=10));".to_string(),
+                location: Location::default(),
+                span: Span::default(),
+            },
+        );
 
         assert_eq!(form, eq_form);
     }
+
+    #[test]
+    fn test_spans() {
+        use super::{Node, XMLForm};
+        use crate::err::Location;
+
+        let xml = "<Strip direction=\"north\" gap=\"@self.gap\">\n    <Panel size=\"remainder\">\n        ui.label(\"hi\");\n    </Panel>\n    <Rust>a();<![CDATA[b();]]></Rust>\n</Strip>";
+
+        let form = XMLForm::try_from(xml.to_string()).unwrap();
+
+        let strip = form.first_child(form.root()).unwrap();
+        let attributes = form.node(strip).get_attributes().unwrap();
+        let gap = attributes.span_of("gap").unwrap();
+
+        assert_eq!(&xml[gap.start..gap.end], "@self.gap");
+
+        let panel = form.first_child(strip).unwrap();
+        let Node::Rust {
+            code,
+            location,
+            span,
+        } = form.node(form.first_child(panel).unwrap())
+        else {
+            panic!("expected Rust code");
+        };
+
+        assert_eq!(&xml[span.start..span.end], code);
+        assert_eq!(*location, Location::new(3, 9));
+
+        let Node::Rust { location, span, .. } = form.node(form.last_child(strip).unwrap()) else {
+            panic!("expected Rust code");
+        };
+
+        assert_eq!(&xml[span.start..span.end], "a();<![CDATA[b();");
+        assert_eq!(*location, Location::new(5, 11));
+    }
+
+    #[test]
+    fn test_splice() {
+        use super::XMLForm;
+
+        let mut form = XMLForm::try_from(
+            "<Panel><Rust>a();</Rust><Include src=\"b.xml\"/><Rust>c();</Rust></Panel>".to_string(),
+        )
+        .unwrap();
+        let fragment = XMLForm::try_from("<Border>b();</Border>\n<Grid/>".to_string())
+            .unwrap()
+            .in_file(1);
+
+        let panel = form.first_child(form.root()).unwrap();
+        let include = form.children(panel)[1];
+
+        let copies = form.splice(include, &fragment);
+
+        assert_eq!(form.parent(include), None);
+        assert_eq!(
+            form.children(panel)
+                .iter()
+                .map(|&id| form.node(id).name())
+                .collect::<Vec<_>>(),
+            vec!["Rust", "Border", "Grid", "Rust"]
+        );
+        assert_eq!(form.node(copies[1]).location().file, 1);
+        assert_eq!(form.node(copies[1]).location().line, 2);
+        assert_eq!(
+            form.node(form.first_child(copies[0]).unwrap())
+                .location()
+                .file,
+            1
+        );
+    }
+
+    #[test]
+    fn test_decode_entities() {
+        use super::decode_entities;
+
+        assert_eq!(decode_entities("a &lt;= b"), "a <= b");
+        assert_eq!(decode_entities("&amp;lt;"), "&lt;");
+        assert_eq!(decode_entities("&#65;&#x42;"), "AB");
+        assert_eq!(
+            decode_entities("ui.add(egui::Slider::new(&mut v, 0..=5));"),
+            "ui.add(egui::Slider::new(&mut v, 0..=5));"
+        );
+        assert_eq!(decode_entities("x & y; &unknown;"), "x & y; &unknown;");
+    }
 }
@@ -0,0 +1,2086 @@
+use strum_macros::EnumString;
+
+use crate::attribute::{
+    parse_code_attribute, parse_hybrid_attribute, parse_literal, parse_optional_hybrid_attribute,
+    parse_optional_rust_attribute, parse_optional_text_attribute, parse_rust_attribute,
+    parse_string, parse_text_attribute, AttributeBool, AttributeColor, AttributeF32,
+    AttributeField, AttributeString, AttributeU32, AttributeVec2, HybridAttribute,
+};
+use crate::core::{Attributes, Node, NodeId, XMLForm};
+use crate::err::ParseError;
+
+/// Attributes describing the size of a cell, allowed on every direct child of a `<Strip>`.
+const SIZE_ATTRIBUTES: &[&str] = &["size", "value", "min", "max"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString)]
+pub enum Direction {
+    #[strum(
+        serialize = "BottomUp",
+        serialize = "bottomup",
+        serialize = "bu",
+        serialize = "south",
+        serialize = "s"
+    )]
+    BottomUp,
+    #[strum(
+        serialize = "LeftToRight",
+        serialize = "lefttoright",
+        serialize = "ltr",
+        serialize = "west",
+        serialize = "w"
+    )]
+    LeftToRight,
+    #[strum(
+        serialize = "RightToLeft",
+        serialize = "righttoleft",
+        serialize = "rtl",
+        serialize = "east",
+        serialize = "e"
+    )]
+    RightToLeft,
+    #[strum(
+        serialize = "TopDown",
+        serialize = "topdown",
+        serialize = "td",
+        serialize = "north",
+        serialize = "n"
+    )]
+    TopDown,
+}
+
+impl Direction {
+    pub fn is_horizontal(&self) -> bool {
+        matches!(self, Direction::LeftToRight | Direction::RightToLeft)
+    }
+
+    /// Whether cells are laid out in the opposite order of the document.
+    pub fn is_reversed(&self) -> bool {
+        matches!(self, Direction::BottomUp | Direction::RightToLeft)
+    }
+}
+
+/// The axes a `<ScrollArea>` scrolls along.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString)]
+pub enum ScrollDirection {
+    #[strum(serialize = "Vertical", serialize = "vertical", serialize = "v")]
+    Vertical,
+    #[strum(serialize = "Horizontal", serialize = "horizontal", serialize = "h")]
+    Horizontal,
+    #[strum(serialize = "Both", serialize = "both")]
+    Both,
+}
+
+/// The corner or edge of the screen a `<Window>` is anchored to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString)]
+pub enum Anchor {
+    #[strum(serialize = "top-left", serialize = "left-top")]
+    TopLeft,
+    #[strum(serialize = "top")]
+    Top,
+    #[strum(serialize = "top-right", serialize = "right-top")]
+    TopRight,
+    #[strum(serialize = "left")]
+    Left,
+    #[strum(serialize = "center")]
+    Center,
+    #[strum(serialize = "right")]
+    Right,
+    #[strum(serialize = "bottom-left", serialize = "left-bottom")]
+    BottomLeft,
+    #[strum(serialize = "bottom")]
+    Bottom,
+    #[strum(serialize = "bottom-right", serialize = "right-bottom")]
+    BottomRight,
+}
+
+/// The edge of the screen an `<SidePanel>`, `<TopPanel>` or `<BottomPanel>` is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString)]
+pub enum PanelSide {
+    #[strum(serialize = "Left", serialize = "left")]
+    Left,
+    #[strum(serialize = "Right", serialize = "right")]
+    Right,
+    /// Only a `<TopPanel>`, not written as a side.
+    #[strum(disabled)]
+    Top,
+    /// Only a `<BottomPanel>`, not written as a side.
+    #[strum(disabled)]
+    Bottom,
+}
+
+impl PanelSide {
+    pub fn is_horizontal(&self) -> bool {
+        matches!(self, PanelSide::Top | PanelSide::Bottom)
+    }
+}
+
+#[derive(PartialEq, Eq, EnumString)]
+enum SizeType {
+    #[strum(serialize = "Remainder", serialize = "remainder", serialize = "rem")]
+    Remainder,
+    #[strum(serialize = "Exact", serialize = "exact", serialize = "ex")]
+    Exact,
+    #[strum(serialize = "Relative", serialize = "relative", serialize = "rel")]
+    Relative,
+}
+
+/// Size of a strip cell, written as `size="..." value="..." min="..." max="..."`.
+#[derive(Clone)]
+pub enum Size {
+    Remainder {
+        min: Option<HybridAttribute<AttributeF32>>,
+        max: Option<HybridAttribute<AttributeF32>>,
+    },
+    Exact {
+        value: HybridAttribute<AttributeF32>,
+
+        min: Option<HybridAttribute<AttributeF32>>,
+        max: Option<HybridAttribute<AttributeF32>>,
+    },
+    Relative {
+        value: HybridAttribute<AttributeF32>,
+
+        min: Option<HybridAttribute<AttributeF32>>,
+        max: Option<HybridAttribute<AttributeF32>>,
+    },
+}
+
+impl TryFrom<&Attributes> for Size {
+    type Error = ParseError;
+
+    fn try_from(attributes: &Attributes) -> Result<Self, Self::Error> {
+        let size_type: SizeType = parse_literal(attributes, "size")?;
+
+        let min = parse_optional_hybrid_attribute::<AttributeF32>(attributes, "min")?;
+
+        let max = parse_optional_hybrid_attribute::<AttributeF32>(attributes, "max")?;
+
+        match size_type {
+            SizeType::Remainder => Ok(Size::Remainder { min, max }),
+            SizeType::Exact => Ok(Size::Exact {
+                value: parse_hybrid_attribute(attributes, "value")?,
+                min,
+                max,
+            }),
+            SizeType::Relative => Ok(Size::Relative {
+                value: parse_hybrid_attribute(attributes, "value")?,
+                min,
+                max,
+            }),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Strip {
+    pub direction: Direction,
+    pub gap: Option<HybridAttribute<AttributeF32>>,
+    pub separator: HybridAttribute<AttributeBool>,
+    /// The `Ui` the strip is built in, `ui` if not given.
+    pub ui: Option<proc_macro2::TokenStream>,
+    pub size: Option<Size>,
+}
+
+impl Strip {
+    const ATTRIBUTES: &'static [&'static str] = &["direction", "gap", "separator", "ui"];
+}
+
+impl TryFrom<&Attributes> for Strip {
+    type Error = ParseError;
+
+    fn try_from(attributes: &Attributes) -> Result<Self, Self::Error> {
+        Ok(Strip {
+            direction: parse_literal(attributes, "direction")?,
+            gap: parse_optional_hybrid_attribute(attributes, "gap")?,
+            separator: parse_optional_hybrid_attribute(attributes, "separator")?
+                .unwrap_or(HybridAttribute::Literal(AttributeBool(false))),
+            ui: parse_optional_rust_attribute(attributes, "ui")?,
+            size: None,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct Panel {
+    pub size: Option<Size>,
+}
+
+/// `<Grid>`, an `egui::Grid` whose `<Row>` children end with `ui.end_row()`.
+#[derive(Clone)]
+pub struct Grid {
+    /// The id salt, a string or `@` Rust code. Derived from the location if not given.
+    pub id: Option<proc_macro2::TokenStream>,
+    pub columns: Option<HybridAttribute<AttributeU32>>,
+    pub striped: Option<HybridAttribute<AttributeBool>>,
+    /// Written `x,y`, e.g. `spacing="8,4"`.
+    pub spacing: Option<HybridAttribute<AttributeVec2>>,
+    pub min_col_width: Option<HybridAttribute<AttributeF32>>,
+    pub size: Option<Size>,
+}
+
+impl Grid {
+    const ATTRIBUTES: &'static [&'static str] =
+        &["id", "columns", "striped", "spacing", "min_col_width"];
+}
+
+impl TryFrom<&Attributes> for Grid {
+    type Error = ParseError;
+
+    fn try_from(attributes: &Attributes) -> Result<Self, Self::Error> {
+        Ok(Grid {
+            id: parse_optional_text_attribute(attributes, "id")?,
+            columns: parse_optional_hybrid_attribute(attributes, "columns")?,
+            striped: parse_optional_hybrid_attribute(attributes, "striped")?,
+            spacing: parse_optional_hybrid_attribute(attributes, "spacing")?,
+            min_col_width: parse_optional_hybrid_attribute(attributes, "min_col_width")?,
+            size: None,
+        })
+    }
+}
+
+/// `<Border>`, an `egui::Frame` around its children.
+#[derive(Clone)]
+pub struct Border {
+    /// Width of the stroke.
+    pub stroke: Option<HybridAttribute<AttributeF32>>,
+    /// Color of the stroke.
+    pub color: Option<HybridAttribute<AttributeColor>>,
+    pub rounding: Option<HybridAttribute<AttributeF32>>,
+    pub inner_margin: Option<HybridAttribute<AttributeF32>>,
+    pub outer_margin: Option<HybridAttribute<AttributeF32>>,
+    pub fill: Option<HybridAttribute<AttributeColor>>,
+    pub size: Option<Size>,
+}
+
+impl Border {
+    const ATTRIBUTES: &'static [&'static str] = &[
+        "stroke",
+        "color",
+        "rounding",
+        "inner_margin",
+        "outer_margin",
+        "fill",
+    ];
+}
+
+impl TryFrom<&Attributes> for Border {
+    type Error = ParseError;
+
+    fn try_from(attributes: &Attributes) -> Result<Self, Self::Error> {
+        Ok(Border {
+            stroke: parse_optional_hybrid_attribute(attributes, "stroke")?,
+            color: parse_optional_hybrid_attribute(attributes, "color")?,
+            rounding: parse_optional_hybrid_attribute(attributes, "rounding")?,
+            inner_margin: parse_optional_hybrid_attribute(attributes, "inner_margin")?,
+            outer_margin: parse_optional_hybrid_attribute(attributes, "outer_margin")?,
+            fill: parse_optional_hybrid_attribute(attributes, "fill")?,
+            size: None,
+        })
+    }
+}
+
+/// `<ScrollArea direction="vertical">`, an `egui::ScrollArea` around its children.
+#[derive(Clone)]
+pub struct ScrollArea {
+    /// Vertical if not given.
+    pub direction: ScrollDirection,
+    /// The id salt, a string or `@` Rust code.
+    pub id: Option<proc_macro2::TokenStream>,
+    pub max_width: Option<HybridAttribute<AttributeF32>>,
+    pub max_height: Option<HybridAttribute<AttributeF32>>,
+    pub auto_shrink: Option<HybridAttribute<AttributeBool>>,
+    pub stick_to_bottom: Option<HybridAttribute<AttributeBool>>,
+    /// An `egui::Vec2` place the scroll offset is read from and written back to.
+    pub offset: Option<proc_macro2::TokenStream>,
+    pub size: Option<Size>,
+}
+
+impl ScrollArea {
+    const ATTRIBUTES: &'static [&'static str] = &[
+        "direction",
+        "id",
+        "max_width",
+        "max_height",
+        "auto_shrink",
+        "stick_to_bottom",
+        "offset",
+    ];
+}
+
+impl TryFrom<&Attributes> for ScrollArea {
+    type Error = ParseError;
+
+    fn try_from(attributes: &Attributes) -> Result<Self, Self::Error> {
+        Ok(ScrollArea {
+            direction: match attributes.get("direction") {
+                Some(_) => parse_literal(attributes, "direction")?,
+                None => ScrollDirection::Vertical,
+            },
+            id: parse_optional_text_attribute(attributes, "id")?,
+            max_width: parse_optional_hybrid_attribute(attributes, "max_width")?,
+            max_height: parse_optional_hybrid_attribute(attributes, "max_height")?,
+            auto_shrink: parse_optional_hybrid_attribute(attributes, "auto_shrink")?,
+            stick_to_bottom: parse_optional_hybrid_attribute(attributes, "stick_to_bottom")?,
+            offset: parse_optional_rust_attribute(attributes, "offset")?,
+            size: None,
+        })
+    }
+}
+
+/// `<Window title="Settings" open="@self.open">`, an `egui::Window` shown in the context.
+#[derive(Clone)]
+pub struct Window {
+    /// A string or `@` Rust code.
+    pub title: proc_macro2::TokenStream,
+    /// The id salt, the title if not given.
+    pub id: Option<proc_macro2::TokenStream>,
+    /// A `bool` place, the close button sets it to `false`.
+    pub open: Option<proc_macro2::TokenStream>,
+    pub resizable: Option<HybridAttribute<AttributeBool>>,
+    pub collapsible: Option<HybridAttribute<AttributeBool>>,
+    /// Written `width,height`.
+    pub default_size: Option<HybridAttribute<AttributeVec2>>,
+    pub anchor: Option<Anchor>,
+    /// Written `x,y`, moves the window away from its anchor.
+    pub anchor_offset: Option<HybridAttribute<AttributeVec2>>,
+    /// The `egui::Context` the window is shown in, `ctx` if not given.
+    pub ctx: Option<proc_macro2::TokenStream>,
+}
+
+impl Window {
+    const ATTRIBUTES: &'static [&'static str] = &[
+        "title",
+        "id",
+        "open",
+        "resizable",
+        "collapsible",
+        "default_size",
+        "anchor",
+        "anchor_offset",
+        "ctx",
+    ];
+}
+
+impl TryFrom<&Attributes> for Window {
+    type Error = ParseError;
+
+    fn try_from(attributes: &Attributes) -> Result<Self, Self::Error> {
+        Ok(Window {
+            title: parse_text_attribute(attributes, "title")?,
+            id: parse_optional_text_attribute(attributes, "id")?,
+            open: parse_optional_rust_attribute(attributes, "open")?,
+            resizable: parse_optional_hybrid_attribute(attributes, "resizable")?,
+            collapsible: parse_optional_hybrid_attribute(attributes, "collapsible")?,
+            default_size: parse_optional_hybrid_attribute(attributes, "default_size")?,
+            anchor: match attributes.get("anchor") {
+                Some(_) => Some(parse_literal(attributes, "anchor")?),
+                None => None,
+            },
+            anchor_offset: parse_optional_hybrid_attribute(attributes, "anchor_offset")?,
+            ctx: parse_optional_rust_attribute(attributes, "ctx")?,
+        })
+    }
+}
+
+/// `<SidePanel side="left">`, `<TopPanel>` or `<BottomPanel>`, a panel at an edge of the
+/// context.
+///
+/// The sizes are widths for a side panel and heights otherwise, e.g. `default_width` and
+/// `default_height`.
+#[derive(Clone)]
+pub struct EdgePanel {
+    pub side: PanelSide,
+    /// The id salt, a string or `@` Rust code. Derived from the location if not given.
+    pub id: Option<proc_macro2::TokenStream>,
+    pub resizable: Option<HybridAttribute<AttributeBool>>,
+    pub default_size: Option<HybridAttribute<AttributeF32>>,
+    pub min_size: Option<HybridAttribute<AttributeF32>>,
+    pub max_size: Option<HybridAttribute<AttributeF32>>,
+    /// The `egui::Context` the panel is shown in, `ctx` if not given.
+    pub ctx: Option<proc_macro2::TokenStream>,
+}
+
+impl EdgePanel {
+    /// The names of the size attributes, `default_width` and so on for a side panel.
+    pub fn size_attributes(side: PanelSide) -> [&'static str; 3] {
+        match side.is_horizontal() {
+            true => ["default_height", "min_height", "max_height"],
+            false => ["default_width", "min_width", "max_width"],
+        }
+    }
+
+    fn from_attributes(attributes: &Attributes, side: PanelSide) -> Result<Self, ParseError> {
+        let [default_size, min_size, max_size] = EdgePanel::size_attributes(side);
+
+        attributes.check(|name| {
+            ["id", "resizable", "ctx", default_size, min_size, max_size].contains(&name)
+                || (name == "side" && !side.is_horizontal())
+        })?;
+
+        Ok(EdgePanel {
+            side,
+            id: parse_optional_text_attribute(attributes, "id")?,
+            resizable: parse_optional_hybrid_attribute(attributes, "resizable")?,
+            default_size: parse_optional_hybrid_attribute(attributes, default_size)?,
+            min_size: parse_optional_hybrid_attribute(attributes, min_size)?,
+            max_size: parse_optional_hybrid_attribute(attributes, max_size)?,
+            ctx: parse_optional_rust_attribute(attributes, "ctx")?,
+        })
+    }
+}
+
+/// `<CentralPanel>`, the space the other panels of the context leave.
+#[derive(Clone)]
+pub struct CentralPanel {
+    /// The `egui::Context` the panel is shown in, `ctx` if not given.
+    pub ctx: Option<proc_macro2::TokenStream>,
+}
+
+/// `<Table>`, an `egui_extras::TableBuilder` with its `<Column>`s, `<Header>` and `<Body>`.
+#[derive(Clone)]
+pub struct Table {
+    /// The id salt, a string or `@` Rust code. Derived from the location if not given.
+    pub id: Option<proc_macro2::TokenStream>,
+    pub striped: Option<HybridAttribute<AttributeBool>>,
+    pub resizable: Option<HybridAttribute<AttributeBool>>,
+    pub size: Option<Size>,
+}
+
+impl Table {
+    const ATTRIBUTES: &'static [&'static str] = &["id", "striped", "resizable"];
+}
+
+impl TryFrom<&Attributes> for Table {
+    type Error = ParseError;
+
+    fn try_from(attributes: &Attributes) -> Result<Self, Self::Error> {
+        Ok(Table {
+            id: parse_optional_text_attribute(attributes, "id")?,
+            striped: parse_optional_hybrid_attribute(attributes, "striped")?,
+            resizable: parse_optional_hybrid_attribute(attributes, "resizable")?,
+            size: None,
+        })
+    }
+}
+
+/// `<Column size="exact" value="120" clip="true"/>`, a column of a `<Table>`.
+///
+/// The width uses the cell size attributes, a relative size is a share of the width the table
+/// starts with. Without a size the column fits its content.
+#[derive(Clone)]
+pub struct Column {
+    pub size: Option<Size>,
+    pub clip: Option<HybridAttribute<AttributeBool>>,
+    pub resizable: Option<HybridAttribute<AttributeBool>>,
+}
+
+impl Column {
+    const ATTRIBUTES: &'static [&'static str] = &["clip", "resizable"];
+}
+
+impl TryFrom<&Attributes> for Column {
+    type Error = ParseError;
+
+    fn try_from(attributes: &Attributes) -> Result<Self, Self::Error> {
+        Ok(Column {
+            size: cell_size(attributes, false)?,
+            clip: parse_optional_hybrid_attribute(attributes, "clip")?,
+            resizable: parse_optional_hybrid_attribute(attributes, "resizable")?,
+        })
+    }
+}
+
+/// `<Header height="20">`, the header row of a `<Table>`, one `<Cell>` per column.
+#[derive(Clone)]
+pub struct Header {
+    pub height: HybridAttribute<AttributeF32>,
+}
+
+/// `<Body row_height="18">`, the `<Row>`s of a `<Table>`.
+#[derive(Clone)]
+pub struct Body {
+    pub row_height: HybridAttribute<AttributeF32>,
+}
+
+/// `<DataGrid rows="@self.orders" sort="@self.sort">`, a table with a row for every item.
+///
+/// Only the rows in view are built. Clicking the header of a sortable `<Column>` sorts by it,
+/// which is kept in the `sort` state, or in the `ui` memory if none is bound. The order is kept
+/// in memory too, and only sorted again when the sort state or the number of rows changes.
+#[derive(Clone)]
+pub struct DataGrid {
+    /// The rows, anything that derefs to a slice.
+    pub rows: proc_macro2::TokenStream,
+    /// An `egui_xml::SortState` place.
+    pub sort: Option<proc_macro2::TokenStream>,
+    /// A hashable value that changes with the rows, the sorted order is kept until it does.
+    pub version: Option<proc_macro2::TokenStream>,
+    /// The pattern every row is bound to in the columns, `row` if not given.
+    pub binding: Option<proc_macro2::TokenStream>,
+    /// Height of the header and of every row, the interact height if not given.
+    pub row_height: Option<HybridAttribute<AttributeF32>>,
+    pub table: Table,
+}
+
+impl DataGrid {
+    const ATTRIBUTES: &'static [&'static str] = &[
+        "rows",
+        "sort",
+        "version",
+        "as",
+        "row_height",
+        "id",
+        "striped",
+        "resizable",
+    ];
+}
+
+impl TryFrom<&Attributes> for DataGrid {
+    type Error = ParseError;
+
+    fn try_from(attributes: &Attributes) -> Result<Self, Self::Error> {
+        Ok(DataGrid {
+            rows: parse_rust_attribute(attributes, "rows")?,
+            sort: parse_optional_rust_attribute(attributes, "sort")?,
+            version: parse_optional_rust_attribute(attributes, "version")?,
+            binding: match attributes.get("as") {
+                Some(_) => Some(parse_code_attribute(attributes, "as")?),
+                None => None,
+            },
+            row_height: parse_optional_hybrid_attribute(attributes, "row_height")?,
+            table: Table::try_from(attributes)?,
+        })
+    }
+}
+
+/// `<Column header="Price" field="price" sortable="true">`, a column of a `<DataGrid>`.
+///
+/// The `field` is a path into the row like `customer.name`, or `@` Rust code using the row
+/// binding. Children replace the label showing the field, with the row bound for them too.
+#[derive(Clone)]
+pub struct DataColumn {
+    /// The header text, a string or `@` Rust code. The field path if not given.
+    pub header: Option<proc_macro2::TokenStream>,
+    pub field: Option<HybridAttribute<AttributeField>>,
+    pub sortable: Option<HybridAttribute<AttributeBool>>,
+    pub column: Column,
+}
+
+impl DataColumn {
+    const ATTRIBUTES: &'static [&'static str] =
+        &["header", "field", "sortable", "clip", "resizable"];
+
+    /// Whether the column can be sorted by, at least while a dynamic `sortable` holds.
+    pub fn maybe_sortable(&self) -> bool {
+        !matches!(
+            self.sortable,
+            None | Some(HybridAttribute::Literal(AttributeBool(false)))
+        )
+    }
+}
+
+impl TryFrom<&Attributes> for DataColumn {
+    type Error = ParseError;
+
+    fn try_from(attributes: &Attributes) -> Result<Self, Self::Error> {
+        let field = parse_optional_hybrid_attribute(attributes, "field")?;
+
+        let header = match (parse_optional_text_attribute(attributes, "header")?, &field) {
+            (None, Some(HybridAttribute::Literal(AttributeField(path)))) => {
+                Some(AttributeString(path.join(".")).into())
+            }
+            (header, _) => header,
+        };
+
+        Ok(DataColumn {
+            header,
+            field,
+            sortable: parse_optional_hybrid_attribute(attributes, "sortable")?,
+            column: Column::try_from(attributes)?,
+        })
+    }
+}
+
+/// `<For each="@items" as="item">`, repeats its children for every item.
+///
+/// Inside a `<Strip>` the children are cells, so the strip gets a cell per child and item.
+#[derive(Clone)]
+pub struct For {
+    /// The expression iterated over.
+    pub each: proc_macro2::TokenStream,
+    /// The pattern every item is bound to, e.g. `item` or `(index, item)`.
+    pub binding: proc_macro2::TokenStream,
+}
+
+impl For {
+    const ATTRIBUTES: &'static [&'static str] = &["each", "as"];
+}
+
+impl TryFrom<&Attributes> for For {
+    type Error = ParseError;
+
+    fn try_from(attributes: &Attributes) -> Result<Self, Self::Error> {
+        Ok(For {
+            each: parse_rust_attribute(attributes, "each")?,
+            binding: parse_code_attribute(attributes, "as")?,
+        })
+    }
+}
+
+/// `<If cond="@...">` or `<ElseIf cond="@...">`, followed by an optional `<Else>`.
+///
+/// An `<If>` inside a `<Strip>` adds its children as cells when the condition holds.
+#[derive(Clone)]
+pub struct If {
+    pub cond: proc_macro2::TokenStream,
+}
+
+impl TryFrom<&Attributes> for If {
+    type Error = ParseError;
+
+    fn try_from(attributes: &Attributes) -> Result<Self, Self::Error> {
+        Ok(If {
+            cond: parse_rust_attribute(attributes, "cond")?,
+        })
+    }
+}
+
+/// `<Match value="@...">`, its `<Case>` children are the arms of a `match`.
+#[derive(Clone)]
+pub struct Match {
+    pub value: proc_macro2::TokenStream,
+    pub size: Option<Size>,
+}
+
+/// `<Case pattern="Page::Home">`, an arm of a `<Match>`.
+///
+/// The pattern is only lexed here, the macro parses it as a pattern.
+#[derive(Clone)]
+pub struct Case {
+    pub pattern: proc_macro2::TokenStream,
+}
+
+/// `<Let name="gap" value="@...">`, a binding its children can use in attributes and code.
+#[derive(Clone)]
+pub struct Let {
+    /// The pattern the value is bound to, usually a name.
+    pub name: proc_macro2::TokenStream,
+    pub value: proc_macro2::TokenStream,
+}
+
+impl TryFrom<&Attributes> for Let {
+    type Error = ParseError;
+
+    fn try_from(attributes: &Attributes) -> Result<Self, Self::Error> {
+        Ok(Let {
+            name: parse_code_attribute(attributes, "name")?,
+            value: parse_rust_attribute(attributes, "value")?,
+        })
+    }
+}
+
+/// `<Slot name="sidebar"/>`, a placeholder for content the macro call passes in.
+#[derive(Clone)]
+pub struct Slot {
+    pub name: String,
+    pub size: Option<Size>,
+}
+
+/// A validated node of a layout.
+#[derive(Clone)]
+pub enum Element {
+    /// The implicit root holding the top-level elements.
+    Root,
+    Rust(proc_macro2::TokenStream),
+    Strip(Strip),
+    Panel(Panel),
+    Grid(Grid),
+    Border(Border),
+    ScrollArea(ScrollArea),
+    Window(Window),
+    EdgePanel(EdgePanel),
+    CentralPanel(CentralPanel),
+    Table(Table),
+    Column(Column),
+    DataGrid(DataGrid),
+    DataColumn(DataColumn),
+    Header(Header),
+    Body(Body),
+    For(For),
+    If(If),
+    ElseIf(If),
+    Else,
+    Match(Match),
+    Case(Case),
+    Let(Let),
+    Slot(Slot),
+    /// A `<Row>` of a `<Grid>`, its children followed by `ui.end_row()`.
+    Row,
+    /// `<EndRow/>`, a bare `ui.end_row()` in a `<Grid>`.
+    EndRow,
+    /// A `<Row>` in the `<Body>` of a `<Table>`.
+    TableRow,
+    /// A `<Cell>` of a table row or header, the contents of one column.
+    Cell,
+    /// A [`Node::Element`] no validation rule knows. Expanders decide what to do with it.
+    Unknown(Unknown),
+}
+
+#[derive(Clone)]
+pub struct Unknown {
+    pub name: String,
+    pub size: Option<Size>,
+}
+
+impl Element {
+    /// The cell size, present on every direct child of a `<Strip>`.
+    pub fn size(&self) -> Option<&Size> {
+        match self {
+            Element::Root
+            | Element::Rust(_)
+            | Element::For(_)
+            | Element::If(_)
+            | Element::ElseIf(_)
+            | Element::Else
+            | Element::Case(_)
+            | Element::Let(_)
+            | Element::Row
+            | Element::EndRow
+            | Element::Window(_)
+            | Element::EdgePanel(_)
+            | Element::CentralPanel(_)
+            | Element::Column(_)
+            | Element::DataColumn(_)
+            | Element::Header(_)
+            | Element::Body(_)
+            | Element::TableRow
+            | Element::Cell => None,
+            Element::Match(info) => info.size.as_ref(),
+            Element::Slot(slot) => slot.size.as_ref(),
+            Element::Strip(strip) => strip.size.as_ref(),
+            Element::Panel(panel) => panel.size.as_ref(),
+            Element::Grid(grid) => grid.size.as_ref(),
+            Element::Border(border) => border.size.as_ref(),
+            Element::ScrollArea(area) => area.size.as_ref(),
+            Element::Table(table) => table.size.as_ref(),
+            Element::DataGrid(grid) => grid.table.size.as_ref(),
+            Element::Unknown(unknown) => unknown.size.as_ref(),
+        }
+    }
+}
+
+/// An [`XMLForm`] whose elements passed validation, with the typed [`Element`] of every node.
+pub struct Layout<'a> {
+    form: &'a XMLForm,
+    elements: Vec<Element>,
+}
+
+impl<'a> Layout<'a> {
+    pub fn form(&self) -> &'a XMLForm {
+        self.form
+    }
+
+    pub fn root(&self) -> NodeId {
+        self.form.root()
+    }
+
+    pub fn element(&self, id: NodeId) -> &Element {
+        &self.elements[id.index()]
+    }
+
+    pub fn children(&self, id: NodeId) -> &'a [NodeId] {
+        self.form.children(id)
+    }
+}
+
+impl<'a> TryFrom<&'a XMLForm> for Layout<'a> {
+    type Error = ParseError;
+
+    fn try_from(form: &'a XMLForm) -> Result<Self, Self::Error> {
+        // nodes a pass took out of the tree, like an expanded `<Use>`, are left unchecked
+        let mut elements = vec![Element::Root; form.len()];
+
+        for id in form.descendants(form.root()) {
+            elements[id.index()] = validate_node(form, id)?;
+        }
+
+        Ok(Layout { form, elements })
+    }
+}
+
+fn validate_node(form: &XMLForm, id: NodeId) -> Result<Element, ParseError> {
+    let parent = form.parent(id).map(|parent| form.node(parent));
+    let in_strip = is_cell(form, id);
+
+    let node = form.node(id);
+
+    // the arms are the only children of a <Match>, a <Slot> or <EndRow> has none
+    let unexpected = match parent.map(Node::name) {
+        Some("Match") => node.name() != "Case",
+        Some("Table") => !["Column", "Header", "Body"].contains(&node.name()),
+        Some("DataGrid") => node.name() != "Column",
+        Some("Slot") | Some("EndRow") => true,
+        _ => false,
+    };
+
+    if let (true, Some(parent)) = (unexpected, parent) {
+        return Err(match node {
+            Node::Rust { location, .. } => ParseError::UnexpectedRust {
+                element: parent.name().to_string(),
+                location: *location,
+            },
+            _ => ParseError::UnexpectedElement {
+                element: node.name().to_string(),
+                parent: parent.name().to_string(),
+                location: node.location(),
+            },
+        });
+    }
+
+    let (attributes, allowed) = match node {
+        Node::Default { .. } => return Ok(Element::Root),
+        Node::Rust { code, location, .. } => {
+            let element = parent.map(Node::name).unwrap_or("Default").to_string();
+
+            if in_strip || in_table(form, id) {
+                return Err(ParseError::UnexpectedRust {
+                    element,
+                    location: *location,
+                });
+            }
+
+            return match code.parse() {
+                Ok(stream) => Ok(Element::Rust(stream)),
+                Err(_) => Err(ParseError::InvalidRust {
+                    element,
+                    attribute: None,
+                    code: code.clone(),
+                    location: *location,
+                }),
+            };
+        }
+        Node::Strip { attributes } => (attributes, Strip::ATTRIBUTES),
+        Node::Grid { attributes } => (attributes, Grid::ATTRIBUTES),
+        Node::Border { attributes } => (attributes, Border::ATTRIBUTES),
+        Node::Panel { attributes } => (attributes, &[][..]),
+        Node::Element { name, attributes }
+            if name == "Row" && container_name(form, id) == Some("Body") =>
+        {
+            attributes.check(|_| false)?;
+
+            return Ok(Element::TableRow);
+        }
+        Node::Element { name, attributes } if name == "Row" || name == "EndRow" => {
+            if !in_grid(form, id) {
+                return Err(ParseError::UnexpectedElement {
+                    element: name.clone(),
+                    parent: parent.map(Node::name).unwrap_or("Default").to_string(),
+                    location: attributes.location(),
+                });
+            }
+
+            attributes.check(|_| false)?;
+
+            return Ok(match name.as_str() {
+                "Row" => Element::Row,
+                _ => Element::EndRow,
+            });
+        }
+        Node::Element { name, attributes } if name == "ScrollArea" => {
+            check_attributes(attributes, ScrollArea::ATTRIBUTES)?;
+
+            return Ok(Element::ScrollArea(ScrollArea {
+                size: cell_size(attributes, in_strip)?,
+                ..ScrollArea::try_from(attributes)?
+            }));
+        }
+        Node::Element { name, attributes } if name == "Window" => {
+            // a window floats above the context, it takes no cell of a strip
+            if in_strip {
+                return Err(ParseError::UnexpectedElement {
+                    element: name.clone(),
+                    parent: parent.map(Node::name).unwrap_or("Default").to_string(),
+                    location: attributes.location(),
+                });
+            }
+
+            attributes.check(|name| Window::ATTRIBUTES.contains(&name))?;
+
+            let window = Window::try_from(attributes)?;
+
+            // below another element there is only a `ui`, the context has to be named
+            let nested = container(form, id).is_some_and(|container| container != form.root());
+
+            if nested && window.ctx.is_none() {
+                return Err(ParseError::MissingAttribute {
+                    element: name.clone(),
+                    attribute: "ctx".to_string(),
+                    location: attributes.location(),
+                });
+            }
+
+            return Ok(Element::Window(window));
+        }
+        Node::Element { name, attributes }
+            if ["SidePanel", "TopPanel", "BottomPanel", "CentralPanel"]
+                .contains(&name.as_str()) =>
+        {
+            // panels fill the context, they only make sense outside of everything else
+            if container(form, id).is_some_and(|container| container != form.root()) {
+                return Err(ParseError::UnexpectedElement {
+                    element: name.clone(),
+                    parent: parent.map(Node::name).unwrap_or("Default").to_string(),
+                    location: attributes.location(),
+                });
+            }
+
+            // egui gives the central panel what is left, the others have to be shown before it
+            let central = form
+                .descendants(form.root())
+                .take_while(|&node| node != id)
+                .any(|node| form.node(node).name() == "CentralPanel");
+
+            if central && name != "CentralPanel" {
+                return Err(ParseError::MisorderedElement {
+                    element: name.clone(),
+                    before: "CentralPanel".to_string(),
+                    location: attributes.location(),
+                });
+            }
+
+            return Ok(match name.as_str() {
+                "SidePanel" => {
+                    let side = parse_literal(attributes, "side")?;
+
+                    Element::EdgePanel(EdgePanel::from_attributes(attributes, side)?)
+                }
+                "TopPanel" => {
+                    Element::EdgePanel(EdgePanel::from_attributes(attributes, PanelSide::Top)?)
+                }
+                "BottomPanel" => {
+                    Element::EdgePanel(EdgePanel::from_attributes(attributes, PanelSide::Bottom)?)
+                }
+                _ => {
+                    attributes.check(|name| name == "ctx")?;
+
+                    Element::CentralPanel(CentralPanel {
+                        ctx: parse_optional_rust_attribute(attributes, "ctx")?,
+                    })
+                }
+            });
+        }
+        Node::Element { name, attributes } if name == "Table" => {
+            check_attributes(attributes, Table::ATTRIBUTES)?;
+
+            return Ok(Element::Table(Table {
+                size: cell_size(attributes, in_strip)?,
+                ..Table::try_from(attributes)?
+            }));
+        }
+        Node::Element { name, attributes } if name == "DataGrid" => {
+            check_attributes(attributes, DataGrid::ATTRIBUTES)?;
+
+            let mut grid = DataGrid::try_from(attributes)?;
+            grid.table.size = cell_size(attributes, in_strip)?;
+
+            return Ok(Element::DataGrid(grid));
+        }
+        Node::Element { name, attributes }
+            if name == "Column" && matches!(parent, Some(node) if node.name() == "DataGrid") =>
+        {
+            check_attributes(attributes, DataColumn::ATTRIBUTES)?;
+
+            let column = DataColumn::try_from(attributes)?;
+
+            // the field is shown and sorted by, a template only replaces showing it
+            if column.field.is_none() && (column.maybe_sortable() || form.children(id).is_empty()) {
+                return Err(ParseError::MissingAttribute {
+                    element: "Column".to_string(),
+                    attribute: "field".to_string(),
+                    location: attributes.location(),
+                });
+            }
+
+            return Ok(Element::DataColumn(column));
+        }
+        Node::Element { name, attributes }
+            if ["Column", "Header", "Body"].contains(&name.as_str()) =>
+        {
+            check_table_section(form, id)?;
+
+            return Ok(match name.as_str() {
+                "Column" => {
+                    check_attributes(attributes, Column::ATTRIBUTES)?;
+
+                    Element::Column(Column::try_from(attributes)?)
+                }
+                "Header" => {
+                    attributes.check(|name| name == "height")?;
+
+                    Element::Header(Header {
+                        height: parse_hybrid_attribute(attributes, "height")?,
+                    })
+                }
+                _ => {
+                    attributes.check(|name| name == "row_height")?;
+
+                    Element::Body(Body {
+                        row_height: parse_hybrid_attribute(attributes, "row_height")?,
+                    })
+                }
+            });
+        }
+        Node::Element { name, attributes } if name == "Cell" => {
+            if !in_table(form, id) || container_name(form, id) == Some("Body") {
+                return Err(ParseError::UnexpectedElement {
+                    element: "Cell".to_string(),
+                    parent: parent.map(Node::name).unwrap_or("Default").to_string(),
+                    location: attributes.location(),
+                });
+            }
+
+            attributes.check(|_| false)?;
+
+            return Ok(Element::Cell);
+        }
+        Node::Element { name, attributes } if name == "For" => {
+            attributes.check(|name| For::ATTRIBUTES.contains(&name))?;
+
+            return Ok(Element::For(For::try_from(attributes)?));
+        }
+        Node::Element { name, attributes } if name == "If" || name == "ElseIf" => {
+            attributes.check(|name| name == "cond")?;
+
+            let info = If::try_from(attributes)?;
+
+            return match name.as_str() {
+                "If" => Ok(Element::If(info)),
+                _ => check_branch(form, id).map(|_| Element::ElseIf(info)),
+            };
+        }
+        Node::Element { name, attributes } if name == "Let" => {
+            attributes.check(|name| name == "name" || name == "value")?;
+
+            return Ok(Element::Let(Let::try_from(attributes)?));
+        }
+        Node::Element { name, attributes } if name == "Slot" => {
+            check_attributes(attributes, &["name"])?;
+
+            return Ok(Element::Slot(Slot {
+                name: parse_string(attributes, "name")?,
+                size: cell_size(attributes, in_strip)?,
+            }));
+        }
+        Node::Element { name, attributes } if name == "Match" => {
+            check_attributes(attributes, &["value"])?;
+
+            return Ok(Element::Match(Match {
+                value: parse_rust_attribute(attributes, "value")?,
+                size: cell_size(attributes, in_strip)?,
+            }));
+        }
+        Node::Element { name, attributes } if name == "Case" => {
+            if !matches!(parent, Some(Node::Element { name, .. }) if name == "Match") {
+                return Err(ParseError::UnexpectedElement {
+                    element: "Case".to_string(),
+                    parent: parent.map(Node::name).unwrap_or("Default").to_string(),
+                    location: attributes.location(),
+                });
+            }
+
+            attributes.check(|name| name == "pattern")?;
+
+            return Ok(Element::Case(Case {
+                pattern: parse_code_attribute(attributes, "pattern")?,
+            }));
+        }
+        Node::Element { name, attributes } if name == "Else" => {
+            attributes.check(|_| false)?;
+
+            return check_branch(form, id).map(|_| Element::Else);
+        }
+        Node::Element { name, attributes } => {
+            // whatever handles the element knows its attributes, only a given size is checked
+            let size = match attributes.get("size") {
+                Some(_) => Some(Size::try_from(attributes)?),
+                None => None,
+            };
+
+            return Ok(Element::Unknown(Unknown {
+                name: name.clone(),
+                size,
+            }));
+        }
+    };
+
+    check_attributes(attributes, allowed)?;
+
+    let size = cell_size(attributes, in_strip)?;
+
+    Ok(match node {
+        Node::Strip { .. } => Element::Strip(Strip {
+            size,
+            ..Strip::try_from(attributes)?
+        }),
+        Node::Panel { .. } => Element::Panel(Panel { size }),
+        Node::Grid { .. } => Element::Grid(Grid {
+            size,
+            ..Grid::try_from(attributes)?
+        }),
+        Node::Border { .. } => Element::Border(Border {
+            size,
+            ..Border::try_from(attributes)?
+        }),
+        Node::Default { .. } | Node::Rust { .. } | Node::Element { .. } => unreachable!(),
+    })
+}
+
+/// Cells of a strip always need a size, everywhere else it is optional.
+fn cell_size(attributes: &Attributes, in_strip: bool) -> Result<Option<Size>, ParseError> {
+    if in_strip || attributes.get("size").is_some() {
+        Size::try_from(attributes).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+fn check_attributes(attributes: &Attributes, allowed: &[&str]) -> Result<(), ParseError> {
+    attributes.check(|name| allowed.contains(&name) || SIZE_ATTRIBUTES.contains(&name))
+}
+
+/// `<ElseIf>` and `<Else>` continue the `<If>` or `<ElseIf>` right before them.
+///
+/// A strip needs a cell for every branch, so they are not allowed among its cells.
+fn check_branch(form: &XMLForm, id: NodeId) -> Result<(), ParseError> {
+    let node = form.node(id);
+
+    if is_cell(form, id) {
+        let parent = form.parent(id).expect("a cell has a parent");
+
+        return Err(ParseError::UnexpectedElement {
+            element: node.name().to_string(),
+            parent: form.node(parent).name().to_string(),
+            location: node.location(),
+        });
+    }
+
+    let follows_if = form.previous_sibling(id).is_some_and(|previous| {
+        matches!(form.node(previous), Node::Element { name, .. } if name == "If" || name == "ElseIf")
+    });
+
+    if !follows_if {
+        return Err(ParseError::OrphanedElse {
+            element: node.name().to_string(),
+            location: node.location(),
+        });
+    }
+
+    Ok(())
+}
+
+/// `<Column>`, `<Header>` and `<Body>` belong directly to a `<Table>`, which has one header
+/// and one body at most.
+fn check_table_section(form: &XMLForm, id: NodeId) -> Result<(), ParseError> {
+    let node = form.node(id);
+    let parent = form.parent(id).map(|parent| form.node(parent).name());
+
+    if parent != Some("Table") {
+        return Err(ParseError::UnexpectedElement {
+            element: node.name().to_string(),
+            parent: parent.unwrap_or("Default").to_string(),
+            location: node.location(),
+        });
+    }
+
+    let repeated = node.name() != "Column"
+        && std::iter::successors(form.previous_sibling(id), |&sibling| {
+            form.previous_sibling(sibling)
+        })
+        .any(|sibling| form.node(sibling).name() == node.name());
+
+    if repeated {
+        return Err(ParseError::DuplicateElement {
+            element: node.name().to_string(),
+            parent: "Table".to_string(),
+            location: node.location(),
+        });
+    }
+
+    Ok(())
+}
+
+/// The nearest ancestor of `id` that is not a `<For>`, `<If>` or other control flow element.
+fn container(form: &XMLForm, id: NodeId) -> Option<NodeId> {
+    const FLOW: &[&str] = &["For", "If", "ElseIf", "Else", "Let", "Match", "Case"];
+
+    form.ancestors(id)
+        .find(|&ancestor| !FLOW.contains(&form.node(ancestor).name()))
+}
+
+fn container_name(form: &XMLForm, id: NodeId) -> Option<&str> {
+    container(form, id).map(|container| form.node(container).name())
+}
+
+/// Whether `id` belongs to a grid, possibly through `<For>`, `<If>` and the like.
+fn in_grid(form: &XMLForm, id: NodeId) -> bool {
+    container(form, id).is_some_and(|container| matches!(form.node(container), Node::Grid { .. }))
+}
+
+/// Whether `id` is laid out by a table, i.e. it is in a `<Header>`, a `<Body>` or a `<Row>` of
+/// one, where there is no `ui` but only rows and cells.
+fn in_table(form: &XMLForm, id: NodeId) -> bool {
+    match container(form, id) {
+        Some(container) => match form.node(container).name() {
+            "Header" | "Body" => true,
+            "Row" => container_name(form, container) == Some("Body"),
+            _ => false,
+        },
+        None => false,
+    }
+}
+
+/// Whether `id` is a cell of a strip, i.e. a child of a `<Strip>` or of a `<For>`, `<If>` or
+/// `<Let>` adding cells to one.
+fn is_cell(form: &XMLForm, id: NodeId) -> bool {
+    let Some(parent) = form.parent(id) else {
+        return false;
+    };
+
+    match form.node(parent) {
+        Node::Strip { .. } => true,
+        Node::Element { name, .. } if ["For", "If", "Let"].contains(&name.as_str()) => {
+            is_cell(form, parent)
+        }
+        _ => false,
+    }
+}
+
+/// The error validating `xml` gives, if any.
+#[cfg(test)]
+fn validate(xml: &str) -> Option<ParseError> {
+    let form = XMLForm::try_from(xml.to_string()).unwrap();
+    Layout::try_from(&form).err()
+}
+
+mod test {
+    #[test]
+    fn test_validate() {
+        use super::{Direction, Element, Layout, Size};
+        use crate::attribute::HybridAttribute;
+        use crate::XMLForm;
+
+        let xml = r#"
+        <Strip direction="west" gap="@gap">
+            <Panel size="exact" value="250.0" min="100">ui.label("left");</Panel>
+            <Strip direction="north" size="remainder">
+                <Panel size="relative" value="0.3"></Panel>
+                <Panel size="remainder"></Panel>
+            </Strip>
+        </Strip>
+        "#;
+
+        let form = XMLForm::try_from(xml.to_string()).unwrap();
+        let layout = Layout::try_from(&form).unwrap();
+
+        let strip = form.first_child(form.root()).unwrap();
+
+        let Element::Strip(outer) = layout.element(strip) else {
+            panic!("expected a strip");
+        };
+
+        assert_eq!(outer.direction, Direction::LeftToRight);
+        assert!(outer.size.is_none());
+        assert!(matches!(outer.gap, Some(HybridAttribute::DynamicRust(_))));
+
+        let panel = form.first_child(strip).unwrap();
+
+        assert!(matches!(
+            layout.element(panel).size(),
+            Some(Size::Exact {
+                value: HybridAttribute::Literal(_),
+                min: Some(_),
+                max: None
+            })
+        ));
+        assert!(matches!(
+            layout.element(form.first_child(panel).unwrap()),
+            Element::Rust(_)
+        ));
+
+        let inner = form.last_child(strip).unwrap();
+
+        assert!(matches!(
+            layout.element(inner),
+            Element::Strip(super::Strip {
+                direction: Direction::TopDown,
+                size: Some(Size::Remainder { .. }),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_validate_errors() {
+        use super::validate;
+        use crate::err::{Location, ParseError};
+
+        assert_eq!(
+            validate("<Strip direction=\"up\"></Strip>"),
+            Some(ParseError::InvalidLiteral {
+                element: "Strip".to_string(),
+                attribute: "direction".to_string(),
+                value: "up".to_string(),
+                location: Location::new(1, 8),
+            })
+        );
+
+        assert_eq!(
+            validate("<Strip direction=\"north\">\n<Panel value=\"1\"></Panel></Strip>"),
+            Some(ParseError::MissingAttribute {
+                element: "Panel".to_string(),
+                attribute: "size".to_string(),
+                location: Location::new(2, 1),
+            })
+        );
+
+        assert_eq!(
+            validate("<Strip direction=\"north\" gapp=\"1\"></Strip>"),
+            Some(ParseError::UnknownAttribute {
+                element: "Strip".to_string(),
+                attribute: "gapp".to_string(),
+                location: Location::new(1, 26),
+            })
+        );
+
+        // unknown elements are left to the expanders, only a given size is checked
+        assert!(
+            validate("<Strip direction=\"north\"><Chart size=\"rem\" kind=\"pie\"/></Strip>")
+                .is_none()
+        );
+        assert_eq!(
+            validate("<Strip direction=\"north\"><Chart size=\"big\"/></Strip>"),
+            Some(ParseError::InvalidLiteral {
+                element: "Chart".to_string(),
+                attribute: "size".to_string(),
+                value: "big".to_string(),
+                location: Location::new(1, 33),
+            })
+        );
+
+        assert_eq!(
+            validate("<Strip direction=\"north\">\n    ui.label(\"lost\");\n</Strip>"),
+            Some(ParseError::UnexpectedRust {
+                element: "Strip".to_string(),
+                location: Location::new(2, 5),
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_for() {
+        use super::{validate, Element, Layout};
+        use crate::err::{Location, ParseError};
+        use crate::XMLForm;
+
+        let xml = r#"
+        <Strip direction="west">
+            <For each="@self.items.iter()" as="(index, item)">
+                <Panel size="exact" value="@item.width">ui.label(index.to_string());</Panel>
+            </For>
+        </Strip>
+        "#;
+
+        let form = XMLForm::try_from(xml.to_string()).unwrap();
+        let layout = Layout::try_from(&form).unwrap();
+
+        let each = form
+            .first_child(form.first_child(form.root()).unwrap())
+            .unwrap();
+
+        let Element::For(info) = layout.element(each) else {
+            panic!("expected a for");
+        };
+
+        assert_eq!(info.each.to_string(), "self . items . iter ()");
+        assert_eq!(info.binding.to_string(), "(index , item)");
+        assert!(layout
+            .element(form.first_child(each).unwrap())
+            .size()
+            .is_some());
+
+        // the children of a <For> in a strip are cells
+        assert_eq!(
+            validate("<Strip direction=\"north\"><For each=\"@items\" as=\"item\">\n<Panel/></For></Strip>"),
+            Some(ParseError::MissingAttribute {
+                element: "Panel".to_string(),
+                attribute: "size".to_string(),
+                location: Location::new(2, 1),
+            })
+        );
+        assert_eq!(
+            validate("<For each=\"items\" as=\"item\"></For>"),
+            Some(ParseError::MissingRustPrefix {
+                element: "For".to_string(),
+                attribute: "each".to_string(),
+                location: Location::new(1, 6),
+            })
+        );
+        assert_eq!(
+            validate("<For each=\"@items\" as=\"item\" size=\"rem\"></For>"),
+            Some(ParseError::UnknownAttribute {
+                element: "For".to_string(),
+                attribute: "size".to_string(),
+                location: Location::new(1, 30),
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_if() {
+        use super::{validate, Element, Layout};
+        use crate::err::{Location, ParseError};
+        use crate::XMLForm;
+
+        let xml = r#"
+        <Panel>
+            <If cond="@a">ui.label("a");</If>
+            <ElseIf cond="@b">ui.label("b");</ElseIf>
+            <Else>ui.label("c");</Else>
+        </Panel>
+        "#;
+
+        let form = XMLForm::try_from(xml.to_string()).unwrap();
+        let layout = Layout::try_from(&form).unwrap();
+
+        let branches: Vec<&Element> = form
+            .children(form.first_child(form.root()).unwrap())
+            .iter()
+            .map(|&id| layout.element(id))
+            .collect();
+
+        assert!(matches!(
+            branches[..],
+            [Element::If(_), Element::ElseIf(_), Element::Else]
+        ));
+
+        assert_eq!(
+            validate("<Panel>\n<Else></Else></Panel>"),
+            Some(ParseError::OrphanedElse {
+                element: "Else".to_string(),
+                location: Location::new(2, 1),
+            })
+        );
+        assert_eq!(
+            validate("<If cond=\"@a\"/>ui.label(\"a\");<ElseIf cond=\"@b\"/>"),
+            Some(ParseError::OrphanedElse {
+                element: "ElseIf".to_string(),
+                location: Location::new(1, 30),
+            })
+        );
+        assert_eq!(
+            validate("<Strip direction=\"north\"><If cond=\"@a\"></If><Else></Else></Strip>"),
+            Some(ParseError::UnexpectedElement {
+                element: "Else".to_string(),
+                parent: "Strip".to_string(),
+                location: Location::new(1, 45),
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_match() {
+        use super::{validate, Element, Layout};
+        use crate::err::{Location, ParseError};
+        use crate::XMLForm;
+
+        let xml = r#"
+        <Strip direction="north">
+            <Match value="@self.page" size="remainder">
+                <Case pattern="Page::Home">ui.label("home");</Case>
+                <Case pattern="_"></Case>
+            </Match>
+        </Strip>
+        "#;
+
+        let form = XMLForm::try_from(xml.to_string()).unwrap();
+        let layout = Layout::try_from(&form).unwrap();
+
+        let matched = form
+            .first_child(form.first_child(form.root()).unwrap())
+            .unwrap();
+
+        assert!(matches!(layout.element(matched), Element::Match(_)));
+        assert!(layout.element(matched).size().is_some());
+
+        let Element::Case(case) = layout.element(form.first_child(matched).unwrap()) else {
+            panic!("expected a case");
+        };
+
+        assert_eq!(case.pattern.to_string(), "Page :: Home");
+
+        assert_eq!(
+            validate("<Panel>\n<Case pattern=\"_\"></Case></Panel>"),
+            Some(ParseError::UnexpectedElement {
+                element: "Case".to_string(),
+                parent: "Panel".to_string(),
+                location: Location::new(2, 1),
+            })
+        );
+        assert_eq!(
+            validate("<Match value=\"@page\">\n<Panel></Panel></Match>"),
+            Some(ParseError::UnexpectedElement {
+                element: "Panel".to_string(),
+                parent: "Match".to_string(),
+                location: Location::new(2, 1),
+            })
+        );
+        assert_eq!(
+            validate("<Match value=\"@page\">\n    ui.label(\"lost\");\n</Match>"),
+            Some(ParseError::UnexpectedRust {
+                element: "Match".to_string(),
+                location: Location::new(2, 5),
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_let() {
+        use super::{validate, Element, Layout};
+        use crate::err::{Location, ParseError};
+        use crate::XMLForm;
+
+        let xml = r#"
+        <Strip direction="north">
+            <Let name="gap" value="@ui.spacing().item_spacing.x * 2.0">
+                <Panel size="exact" value="@gap"></Panel>
+            </Let>
+        </Strip>
+        "#;
+
+        let form = XMLForm::try_from(xml.to_string()).unwrap();
+        let layout = Layout::try_from(&form).unwrap();
+
+        let binding = form
+            .first_child(form.first_child(form.root()).unwrap())
+            .unwrap();
+
+        let Element::Let(info) = layout.element(binding) else {
+            panic!("expected a let");
+        };
+
+        assert_eq!(info.name.to_string(), "gap");
+        assert!(layout
+            .element(form.first_child(binding).unwrap())
+            .size()
+            .is_some());
+
+        assert_eq!(
+            validate("<Let name=\"gap\" value=\"4.0\"></Let>"),
+            Some(ParseError::MissingRustPrefix {
+                element: "Let".to_string(),
+                attribute: "value".to_string(),
+                location: Location::new(1, 17),
+            })
+        );
+        assert_eq!(
+            validate("<Strip direction=\"north\"><Let name=\"gap\" value=\"@4.0\">\n    gap;\n</Let></Strip>"),
+            Some(ParseError::UnexpectedRust {
+                element: "Let".to_string(),
+                location: Location::new(2, 5),
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_slot() {
+        use super::{validate, Element, Layout};
+        use crate::err::{Location, ParseError};
+        use crate::XMLForm;
+
+        let xml =
+            r#"<Strip direction="west"><Slot name="sidebar" size="exact" value="150"/></Strip>"#;
+
+        let form = XMLForm::try_from(xml.to_string()).unwrap();
+        let layout = Layout::try_from(&form).unwrap();
+
+        let slot = form
+            .first_child(form.first_child(form.root()).unwrap())
+            .unwrap();
+
+        let Element::Slot(info) = layout.element(slot) else {
+            panic!("expected a slot");
+        };
+
+        assert_eq!(info.name, "sidebar");
+        assert!(info.size.is_some());
+
+        assert_eq!(
+            validate("<Slot name=\"sidebar\">\n    ui.label(\"default\");\n</Slot>"),
+            Some(ParseError::UnexpectedRust {
+                element: "Slot".to_string(),
+                location: Location::new(2, 5),
+            })
+        );
+        assert_eq!(
+            validate("<Panel><Slot/></Panel>"),
+            Some(ParseError::MissingAttribute {
+                element: "Slot".to_string(),
+                attribute: "name".to_string(),
+                location: Location::new(1, 8),
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_grid() {
+        use super::{validate, Element, Layout};
+        use crate::attribute::HybridAttribute;
+        use crate::err::{Location, ParseError};
+        use crate::XMLForm;
+
+        let xml = r#"
+        <Grid id="@self.id" columns="2" striped="true" spacing="8, 4" min_col_width="@width">
+            <For each="@rows" as="row">
+                <Row>ui.label(row);</Row>
+            </For>
+            <EndRow/>
+        </Grid>
+        "#;
+
+        let form = XMLForm::try_from(xml.to_string()).unwrap();
+        let layout = Layout::try_from(&form).unwrap();
+
+        let grid = form.first_child(form.root()).unwrap();
+
+        let Element::Grid(info) = layout.element(grid) else {
+            panic!("expected a grid");
+        };
+
+        assert_eq!(info.id.as_ref().unwrap().to_string(), "self . id");
+        assert!(matches!(
+            info.spacing,
+            Some(HybridAttribute::Literal(super::AttributeVec2(8.0, 4.0)))
+        ));
+        assert!(matches!(
+            info.min_col_width,
+            Some(HybridAttribute::DynamicRust(_))
+        ));
+
+        let row = form.first_child(form.first_child(grid).unwrap()).unwrap();
+
+        assert!(matches!(layout.element(row), Element::Row));
+        assert!(matches!(
+            layout.element(form.last_child(grid).unwrap()),
+            Element::EndRow
+        ));
+
+        assert_eq!(
+            validate("<Panel>\n<Row></Row></Panel>"),
+            Some(ParseError::UnexpectedElement {
+                element: "Row".to_string(),
+                parent: "Panel".to_string(),
+                location: Location::new(2, 1),
+            })
+        );
+        assert_eq!(
+            validate("<Grid spacing=\"8;4\"></Grid>"),
+            Some(ParseError::InvalidLiteral {
+                element: "Grid".to_string(),
+                attribute: "spacing".to_string(),
+                value: "8;4".to_string(),
+                location: Location::new(1, 7),
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_border() {
+        use super::{validate, AttributeColor, Element, Layout};
+        use crate::attribute::HybridAttribute;
+        use crate::err::{Location, ParseError};
+        use crate::XMLForm;
+
+        let xml = r##"<Border stroke="1.5" color="#444" rounding="@radius" fill="#10203040"/>"##;
+
+        let form = XMLForm::try_from(xml.to_string()).unwrap();
+        let layout = Layout::try_from(&form).unwrap();
+
+        let Element::Border(info) = layout.element(form.first_child(form.root()).unwrap()) else {
+            panic!("expected a border");
+        };
+
+        assert!(matches!(
+            info.color,
+            Some(HybridAttribute::Literal(AttributeColor([
+                0x44, 0x44, 0x44, 255
+            ])))
+        ));
+        assert!(matches!(
+            info.fill,
+            Some(HybridAttribute::Literal(AttributeColor([
+                0x10, 0x20, 0x30, 0x40
+            ])))
+        ));
+        assert!(matches!(
+            info.rounding,
+            Some(HybridAttribute::DynamicRust(_))
+        ));
+        assert!(info.inner_margin.is_none());
+
+        assert_eq!(
+            validate("<Border color=\"#12\"/>"),
+            Some(ParseError::InvalidLiteral {
+                element: "Border".to_string(),
+                attribute: "color".to_string(),
+                value: "#12".to_string(),
+                location: Location::new(1, 9),
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_table() {
+        use super::{validate, Element, Layout, Size};
+        use crate::attribute::HybridAttribute;
+        use crate::err::{Location, ParseError};
+        use crate::XMLForm;
+
+        let xml = r#"
+        <Table striped="true" resizable="@self.resizable">
+            <Column size="exact" value="120" clip="true"/>
+            <Column/>
+            <Header height="20"><Cell>ui.strong("Name");</Cell></Header>
+            <Body row_height="18">
+                <For each="@rows" as="row">
+                    <Row><Cell>ui.label(row);</Cell></Row>
+                </For>
+            </Body>
+        </Table>
+        "#;
+
+        let form = XMLForm::try_from(xml.to_string()).unwrap();
+        let layout = Layout::try_from(&form).unwrap();
+
+        let table = form.first_child(form.root()).unwrap();
+
+        let Element::Table(info) = layout.element(table) else {
+            panic!("expected a table");
+        };
+
+        assert!(matches!(
+            info.resizable,
+            Some(HybridAttribute::DynamicRust(_))
+        ));
+
+        let columns: Vec<_> = form.children(table)[..2]
+            .iter()
+            .map(|&column| layout.element(column))
+            .collect();
+
+        assert!(matches!(
+            columns[0],
+            Element::Column(column) if matches!(column.size, Some(Size::Exact { .. }))
+                && column.clip.is_some()
+        ));
+        assert!(matches!(columns[1], Element::Column(column) if column.size.is_none()));
+
+        let body = form.last_child(table).unwrap();
+        let row = form.first_child(form.first_child(body).unwrap()).unwrap();
+
+        assert!(matches!(layout.element(body), Element::Body(_)));
+        assert!(matches!(layout.element(row), Element::TableRow));
+        assert!(matches!(
+            layout.element(form.first_child(row).unwrap()),
+            Element::Cell
+        ));
+
+        assert_eq!(
+            validate("<Table>\n<Panel/></Table>"),
+            Some(ParseError::UnexpectedElement {
+                element: "Panel".to_string(),
+                parent: "Table".to_string(),
+                location: Location::new(2, 1),
+            })
+        );
+        assert_eq!(
+            validate("<Table><Body row_height=\"18\"/>\n<Body row_height=\"18\"/></Table>"),
+            Some(ParseError::DuplicateElement {
+                element: "Body".to_string(),
+                parent: "Table".to_string(),
+                location: Location::new(2, 1),
+            })
+        );
+        assert_eq!(
+            validate("<Table><Body row_height=\"18\">\nui.label(\"a\");</Body></Table>"),
+            Some(ParseError::UnexpectedRust {
+                element: "Body".to_string(),
+                location: Location::new(2, 1),
+            })
+        );
+        assert_eq!(
+            validate("<Table><Header height=\"20\">\n<Row/></Header></Table>"),
+            Some(ParseError::UnexpectedElement {
+                element: "Row".to_string(),
+                parent: "Header".to_string(),
+                location: Location::new(2, 1),
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_data_grid() {
+        use super::{validate, AttributeField, Element, Layout};
+        use crate::attribute::HybridAttribute;
+        use crate::err::{Location, ParseError};
+        use crate::XMLForm;
+
+        let xml = r#"
+        <DataGrid rows="@self.orders" sort="@self.sort" version="@self.version" as="order">
+            <Column field="customer.name" sortable="true" size="exact" value="120"/>
+            <Column header="Total" field="@order.total()">ui.label("total");</Column>
+        </DataGrid>
+        "#;
+
+        let form = XMLForm::try_from(xml.to_string()).unwrap();
+        let layout = Layout::try_from(&form).unwrap();
+
+        let grid = form.first_child(form.root()).unwrap();
+
+        let Element::DataGrid(info) = layout.element(grid) else {
+            panic!("expected a data grid");
+        };
+
+        assert_eq!(info.sort.as_ref().unwrap().to_string(), "self . sort");
+        assert_eq!(info.version.as_ref().unwrap().to_string(), "self . version");
+        assert_eq!(info.binding.as_ref().unwrap().to_string(), "order");
+
+        let Element::DataColumn(name) = layout.element(form.children(grid)[0]) else {
+            panic!("expected a column");
+        };
+
+        assert!(name.maybe_sortable());
+        assert_eq!(
+            name.header.as_ref().unwrap().to_string(),
+            "\"customer.name\""
+        );
+        assert!(matches!(
+            &name.field,
+            Some(HybridAttribute::Literal(AttributeField(path))) if path == &["customer", "name"]
+        ));
+
+        let Element::DataColumn(total) = layout.element(form.children(grid)[1]) else {
+            panic!("expected a column");
+        };
+
+        assert!(!total.maybe_sortable());
+        assert!(matches!(total.field, Some(HybridAttribute::DynamicRust(_))));
+
+        assert_eq!(
+            validate("<DataGrid rows=\"@rows\">\n<Column sortable=\"true\">ui.label(\"a\");</Column></DataGrid>"),
+            Some(ParseError::MissingAttribute {
+                element: "Column".to_string(),
+                attribute: "field".to_string(),
+                location: Location::new(2, 1),
+            })
+        );
+        assert_eq!(
+            validate("<DataGrid rows=\"@rows\"><Column sortable=\"@self.sortable\">ui.label(\"a\");</Column></DataGrid>"),
+            Some(ParseError::MissingAttribute {
+                element: "Column".to_string(),
+                attribute: "field".to_string(),
+                location: Location::new(1, 24),
+            })
+        );
+        assert_eq!(
+            validate("<DataGrid rows=\"@rows\"><Column field=\"price-1\"/></DataGrid>"),
+            Some(ParseError::InvalidLiteral {
+                element: "Column".to_string(),
+                attribute: "field".to_string(),
+                value: "price-1".to_string(),
+                location: Location::new(1, 32),
+            })
+        );
+        assert_eq!(
+            validate("<DataGrid rows=\"@rows\"><Column field=\"@price\"/></DataGrid>"),
+            Some(ParseError::UnneededRustPrefix {
+                element: "Column".to_string(),
+                attribute: "field".to_string(),
+                location: Location::new(1, 32),
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_scroll_area() {
+        use super::{validate, Element, Layout, ScrollDirection};
+        use crate::attribute::HybridAttribute;
+        use crate::err::{Location, ParseError};
+        use crate::XMLForm;
+
+        let xml = r#"<ScrollArea direction="both" max_height="200" stick_to_bottom="@follow" offset="@self.offset"/>"#;
+
+        let form = XMLForm::try_from(xml.to_string()).unwrap();
+        let layout = Layout::try_from(&form).unwrap();
+
+        let Element::ScrollArea(info) = layout.element(form.first_child(form.root()).unwrap())
+        else {
+            panic!("expected a scroll area");
+        };
+
+        assert_eq!(info.direction, ScrollDirection::Both);
+        assert!(matches!(info.max_height, Some(HybridAttribute::Literal(_))));
+        assert!(matches!(
+            info.stick_to_bottom,
+            Some(HybridAttribute::DynamicRust(_))
+        ));
+        assert_eq!(info.offset.as_ref().unwrap().to_string(), "self . offset");
+
+        assert!(validate("<ScrollArea/>").is_none());
+        assert_eq!(
+            validate("<ScrollArea direction=\"diagonal\"/>"),
+            Some(ParseError::InvalidLiteral {
+                element: "ScrollArea".to_string(),
+                attribute: "direction".to_string(),
+                value: "diagonal".to_string(),
+                location: Location::new(1, 13),
+            })
+        );
+        assert_eq!(
+            validate("<ScrollArea offset=\"self.offset\"/>"),
+            Some(ParseError::MissingRustPrefix {
+                element: "ScrollArea".to_string(),
+                attribute: "offset".to_string(),
+                location: Location::new(1, 13),
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_window() {
+        use super::{validate, Anchor, Element, Layout};
+        use crate::attribute::HybridAttribute;
+        use crate::err::{Location, ParseError};
+        use crate::XMLForm;
+
+        let xml = r#"<Window title="@self.title()" open="@self.open" default_size="400,300" anchor="top-right"/>"#;
+
+        let form = XMLForm::try_from(xml.to_string()).unwrap();
+        let layout = Layout::try_from(&form).unwrap();
+
+        let Element::Window(info) = layout.element(form.first_child(form.root()).unwrap()) else {
+            panic!("expected a window");
+        };
+
+        assert_eq!(info.title.to_string(), "self . title ()");
+        assert_eq!(info.open.as_ref().unwrap().to_string(), "self . open");
+        assert!(matches!(
+            info.default_size,
+            Some(HybridAttribute::Literal(super::AttributeVec2(400.0, 300.0)))
+        ));
+        assert_eq!(info.anchor, Some(Anchor::TopRight));
+        assert!(info.ctx.is_none());
+
+        assert_eq!(
+            validate("<Window open=\"@open\"/>"),
+            Some(ParseError::MissingAttribute {
+                element: "Window".to_string(),
+                attribute: "title".to_string(),
+                location: Location::new(1, 1),
+            })
+        );
+        assert_eq!(
+            validate("<Window title=\"a\" anchor=\"middle\"/>"),
+            Some(ParseError::InvalidLiteral {
+                element: "Window".to_string(),
+                attribute: "anchor".to_string(),
+                value: "middle".to_string(),
+                location: Location::new(1, 19),
+            })
+        );
+        assert_eq!(
+            validate("<Strip direction=\"east\">\n    <Window title=\"a\" size=\"remainder\"/>\n</Strip>"),
+            Some(ParseError::UnexpectedElement {
+                element: "Window".to_string(),
+                parent: "Strip".to_string(),
+                location: Location::new(2, 5),
+            })
+        );
+        assert_eq!(
+            validate("<Panel>\n    <If cond=\"@open\"><Window title=\"a\"/></If>\n</Panel>"),
+            Some(ParseError::MissingAttribute {
+                element: "Window".to_string(),
+                attribute: "ctx".to_string(),
+                location: Location::new(2, 22),
+            })
+        );
+        assert!(validate("<Panel><Window title=\"a\" ctx=\"@ui.ctx()\"/></Panel>").is_none());
+        assert!(validate("<If cond=\"@open\"><Window title=\"a\"/></If>").is_none());
+    }
+
+    #[test]
+    fn test_validate_panels() {
+        use super::{validate, Element, Layout, PanelSide};
+        use crate::err::{Location, ParseError};
+        use crate::XMLForm;
+
+        let xml = r#"
+        <TopPanel default_height="24"/>
+        <If cond="@self.nav">
+            <SidePanel side="right" id="nav" resizable="true" default_width="200"/>
+        </If>
+        <CentralPanel ctx="@ui.ctx()"/>
+        "#;
+
+        let form = XMLForm::try_from(xml.to_string()).unwrap();
+        let layout = Layout::try_from(&form).unwrap();
+
+        let children = form.children(form.root());
+
+        assert!(matches!(
+            layout.element(children[0]),
+            Element::EdgePanel(panel) if panel.side == PanelSide::Top && panel.default_size.is_some()
+        ));
+        assert!(matches!(
+            layout.element(form.first_child(children[1]).unwrap()),
+            Element::EdgePanel(panel) if panel.side == PanelSide::Right && panel.id.is_some()
+        ));
+        assert!(matches!(
+            layout.element(children[2]),
+            Element::CentralPanel(panel) if panel.ctx.is_some()
+        ));
+
+        assert_eq!(
+            validate("<Border>\n<CentralPanel/></Border>"),
+            Some(ParseError::UnexpectedElement {
+                element: "CentralPanel".to_string(),
+                parent: "Border".to_string(),
+                location: Location::new(2, 1),
+            })
+        );
+        assert_eq!(
+            validate("<SidePanel side=\"top\"/>"),
+            Some(ParseError::InvalidLiteral {
+                element: "SidePanel".to_string(),
+                attribute: "side".to_string(),
+                value: "top".to_string(),
+                location: Location::new(1, 12),
+            })
+        );
+        assert_eq!(
+            validate("<TopPanel default_width=\"200\"/>"),
+            Some(ParseError::UnknownAttribute {
+                element: "TopPanel".to_string(),
+                attribute: "default_width".to_string(),
+                location: Location::new(1, 11),
+            })
+        );
+        assert_eq!(
+            validate("<CentralPanel/>\n<If cond=\"@nav\">\n    <SidePanel side=\"left\"/>\n</If>"),
+            Some(ParseError::MisorderedElement {
+                element: "SidePanel".to_string(),
+                before: "CentralPanel".to_string(),
+                location: Location::new(3, 5),
+            })
+        );
+    }
+}
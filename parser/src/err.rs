@@ -1 +1,372 @@
+use std::fmt;
 
+/// A 1-based line/column position inside the parsed XML source.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Location {
+    /// Index of the source the position is in when a layout is read from several files,
+    /// 0 for the main one. See [`XMLForm::in_file`](crate::XMLForm::in_file).
+    pub file: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    pub fn new(line: usize, column: usize) -> Self {
+        Location {
+            file: 0,
+            line,
+            column,
+        }
+    }
+
+    /// Computes the line/column of a byte offset into `source`.
+    ///
+    /// Columns are counted in characters, not bytes, so they match what editors show.
+    pub fn from_offset(source: &str, offset: usize) -> Self {
+        let offset = offset.min(source.len());
+        let before = &source.as_bytes()[..offset];
+
+        let line = before.iter().filter(|&&byte| byte == b'\n').count() + 1;
+        let line_start = before
+            .iter()
+            .rposition(|&byte| byte == b'\n')
+            .map(|index| index + 1)
+            .unwrap_or(0);
+
+        let column = String::from_utf8_lossy(&before[line_start..])
+            .chars()
+            .count()
+            + 1;
+
+        Location::new(line, column)
+    }
+
+    /// The inverse of [`Location::from_offset`], clamped to the end of `source`.
+    pub fn to_offset(&self, source: &str) -> usize {
+        let line_start = match self.line {
+            0 | 1 => 0,
+            line => source
+                .match_indices('\n')
+                .nth(line - 2)
+                .map(|(index, _)| index + 1)
+                .unwrap_or(source.len()),
+        };
+
+        source[line_start..]
+            .char_indices()
+            .nth(self.column.saturating_sub(1))
+            .map(|(index, _)| line_start + index)
+            .unwrap_or(source.len())
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// A byte range inside the parsed XML source.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+}
+
+/// Everything that can go wrong while turning XML into a layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The document is not well-formed XML.
+    MalformedXml { message: String, location: Location },
+    /// A tag that is not one of the known layout elements, reported when generating code.
+    UnknownElement { element: String, location: Location },
+    /// A required attribute is not present on an element.
+    MissingAttribute {
+        element: String,
+        attribute: String,
+        location: Location,
+    },
+    /// An attribute the element does not know.
+    UnknownAttribute {
+        element: String,
+        attribute: String,
+        location: Location,
+    },
+    /// An attribute value could not be parsed as the literal the element expects.
+    InvalidLiteral {
+        element: String,
+        attribute: String,
+        value: String,
+        location: Location,
+    },
+    /// An `@` attribute value or a Rust block is not valid Rust.
+    InvalidRust {
+        element: String,
+        attribute: Option<String>,
+        code: String,
+        location: Location,
+    },
+    /// An attribute that only accepts Rust code is missing the leading `@`.
+    MissingRustPrefix {
+        element: String,
+        attribute: String,
+        location: Location,
+    },
+    /// The file of an `<Include>` could not be found or read.
+    IncludeNotFound {
+        src: String,
+        tried: Vec<String>,
+        location: Location,
+    },
+    /// An `<Include>` ends up including the file it is in.
+    IncludeCycle {
+        chain: Vec<String>,
+        location: Location,
+    },
+    /// A `<Use>` names a template that is not defined.
+    UnknownTemplate {
+        template: String,
+        location: Location,
+    },
+    /// Two `<Template>`s have the same name.
+    DuplicateTemplate {
+        template: String,
+        location: Location,
+    },
+    /// A template ends up using itself.
+    RecursiveTemplate {
+        template: String,
+        location: Location,
+    },
+    /// A `$name` in a template that is not one of its parameters.
+    UnknownParameter {
+        template: String,
+        parameter: String,
+        location: Location,
+    },
+    /// An attribute is prefixed with `@` although its value is a plain literal.
+    UnneededRustPrefix {
+        element: String,
+        attribute: String,
+        location: Location,
+    },
+    /// Rust code appears where only elements are allowed.
+    UnexpectedRust { element: String, location: Location },
+    /// An element that is not allowed inside its parent.
+    UnexpectedElement {
+        element: String,
+        parent: String,
+        location: Location,
+    },
+    /// An element that may appear only once inside its parent.
+    DuplicateElement {
+        element: String,
+        parent: String,
+        location: Location,
+    },
+    /// An element that has to come before an element `before`, but follows it.
+    MisorderedElement {
+        element: String,
+        before: String,
+        location: Location,
+    },
+    /// An `<ElseIf>` or `<Else>` that does not follow an `<If>` or `<ElseIf>`.
+    OrphanedElse { element: String, location: Location },
+    /// A `<Slot>` the macro call does not pass content for.
+    MissingSlot { slot: String, location: Location },
+}
+
+impl ParseError {
+    pub fn location(&self) -> Location {
+        match self {
+            ParseError::MalformedXml { location, .. }
+            | ParseError::UnknownElement { location, .. }
+            | ParseError::MissingAttribute { location, .. }
+            | ParseError::UnknownAttribute { location, .. }
+            | ParseError::InvalidLiteral { location, .. }
+            | ParseError::InvalidRust { location, .. }
+            | ParseError::MissingRustPrefix { location, .. }
+            | ParseError::IncludeNotFound { location, .. }
+            | ParseError::IncludeCycle { location, .. }
+            | ParseError::UnknownTemplate { location, .. }
+            | ParseError::DuplicateTemplate { location, .. }
+            | ParseError::RecursiveTemplate { location, .. }
+            | ParseError::UnknownParameter { location, .. }
+            | ParseError::UnneededRustPrefix { location, .. }
+            | ParseError::UnexpectedRust { location, .. }
+            | ParseError::UnexpectedElement { location, .. }
+            | ParseError::DuplicateElement { location, .. }
+            | ParseError::MisorderedElement { location, .. }
+            | ParseError::OrphanedElse { location, .. }
+            | ParseError::MissingSlot { location, .. } => *location,
+        }
+    }
+
+    /// The same error located in source `file`, see [`Location::file`].
+    pub fn in_file(mut self, file: usize) -> Self {
+        let location = match &mut self {
+            ParseError::MalformedXml { location, .. }
+            | ParseError::UnknownElement { location, .. }
+            | ParseError::MissingAttribute { location, .. }
+            | ParseError::UnknownAttribute { location, .. }
+            | ParseError::InvalidLiteral { location, .. }
+            | ParseError::InvalidRust { location, .. }
+            | ParseError::MissingRustPrefix { location, .. }
+            | ParseError::IncludeNotFound { location, .. }
+            | ParseError::IncludeCycle { location, .. }
+            | ParseError::UnknownTemplate { location, .. }
+            | ParseError::DuplicateTemplate { location, .. }
+            | ParseError::RecursiveTemplate { location, .. }
+            | ParseError::UnknownParameter { location, .. }
+            | ParseError::UnneededRustPrefix { location, .. }
+            | ParseError::UnexpectedRust { location, .. }
+            | ParseError::UnexpectedElement { location, .. }
+            | ParseError::DuplicateElement { location, .. }
+            | ParseError::MisorderedElement { location, .. }
+            | ParseError::OrphanedElse { location, .. }
+            | ParseError::MissingSlot { location, .. } => location,
+        };
+
+        location.file = file;
+        self
+    }
+
+    /// The error description without the location prefix.
+    pub fn message(&self) -> String {
+        match self {
+            ParseError::MalformedXml { message, .. } => {
+                format!("malformed XML: {}", message)
+            }
+            ParseError::UnknownElement { element, .. } => {
+                format!("unknown element <{}>", element)
+            }
+            ParseError::MissingAttribute {
+                element, attribute, ..
+            } => format!("<{}> is missing the attribute `{}`", element, attribute),
+            ParseError::UnknownAttribute {
+                element, attribute, ..
+            } => format!("<{}> has no attribute `{}`", element, attribute),
+            ParseError::InvalidLiteral {
+                element,
+                attribute,
+                value,
+                ..
+            } => format!(
+                "invalid value {:?} for attribute `{}` of <{}>",
+                value, attribute, element
+            ),
+            ParseError::InvalidRust {
+                element,
+                attribute: Some(attribute),
+                code,
+                ..
+            } => format!(
+                "attribute `{}` of <{}> is not valid Rust: {:?}",
+                attribute, element, code
+            ),
+            ParseError::InvalidRust {
+                element,
+                attribute: None,
+                code,
+                ..
+            } => format!(
+                "Rust code inside <{}> is not valid Rust: {:?}",
+                element, code
+            ),
+            ParseError::MissingRustPrefix {
+                element, attribute, ..
+            } => format!(
+                "attribute `{}` of <{}> must be Rust code starting with @",
+                attribute, element
+            ),
+            ParseError::IncludeNotFound { src, tried, .. } => format!(
+                "included file {} not found, tried:{}",
+                src,
+                tried
+                    .iter()
+                    .map(|path| format!("\n    {}", path))
+                    .collect::<String>()
+            ),
+            ParseError::IncludeCycle { chain, .. } => {
+                format!("include cycle: {}", chain.join(" -> "))
+            }
+            ParseError::UnknownTemplate { template, .. } => {
+                format!("no template named {:?}", template)
+            }
+            ParseError::DuplicateTemplate { template, .. } => {
+                format!("template {:?} is defined twice", template)
+            }
+            ParseError::RecursiveTemplate { template, .. } => {
+                format!("template {:?} uses itself", template)
+            }
+            ParseError::UnknownParameter {
+                template,
+                parameter,
+                ..
+            } => format!("template {:?} has no parameter `{}`", template, parameter),
+            ParseError::UnneededRustPrefix {
+                element, attribute, ..
+            } => format!(
+                "attribute `{}` of <{}> is a plain literal, remove the @",
+                attribute, element
+            ),
+            ParseError::UnexpectedRust { element, .. } => format!(
+                "Rust code is not allowed as a direct child of <{}>",
+                element
+            ),
+            ParseError::UnexpectedElement {
+                element, parent, ..
+            } => format!("<{}> is not allowed inside <{}>", element, parent),
+            ParseError::DuplicateElement {
+                element, parent, ..
+            } => format!("<{}> appears more than once in <{}>", element, parent),
+            ParseError::MisorderedElement {
+                element, before, ..
+            } => format!("<{}> must come before the <{}>", element, before),
+            ParseError::OrphanedElse { element, .. } => {
+                format!("<{}> must directly follow an <If> or <ElseIf>", element)
+            }
+            ParseError::MissingSlot { slot, .. } => format!(
+                "slot `{}` is not filled, pass `{} => {{ ... }}` to the macro",
+                slot, slot
+            ),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location(), self.message())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+mod test {
+    #[test]
+    fn test_location_from_offset() {
+        use super::Location;
+
+        let source = "<Strip>\n    <Panel size=\"rem\">\n</Strip>";
+
+        assert_eq!(Location::from_offset(source, 0), Location::new(1, 1));
+        assert_eq!(Location::from_offset(source, 12), Location::new(2, 5));
+        assert_eq!(
+            Location::from_offset(source, source.len()),
+            Location::new(3, 9)
+        );
+
+        for offset in [0, 12, 20, source.len()] {
+            assert_eq!(
+                Location::from_offset(source, offset).to_offset(source),
+                offset
+            );
+        }
+    }
+}
@@ -1,87 +1,56 @@
-use crate::core::Node;
+use std::fmt;
 
-fn calculate_nested_amount(node: &Node) -> usize {
-    if node.get_parent().is_none() {
-        return 0;
-    }
+use crate::core::{Node, NodeId, XMLForm};
+use crate::visit::{visit_children, Visit};
 
-    let mut amount = 1;
+/// Writes the indented pseudo-XML of [`XMLForm`]'s `Debug` output.
+struct DebugWriter<'f, 'g> {
+    f: &'f mut fmt::Formatter<'g>,
+    result: fmt::Result,
+}
 
-    let mut current_node = node.get_parent().unwrap();
+impl DebugWriter<'_, '_> {
+    fn write(&mut self, args: fmt::Arguments) {
+        if self.result.is_ok() {
+            self.result = self.f.write_fmt(args);
+        }
+    }
+}
 
-    while current_node.borrow().get_parent().is_some() {
-        amount += 1;
+impl Visit<'_> for DebugWriter<'_, '_> {
+    fn visit_node(&mut self, form: &XMLForm, id: NodeId) {
+        let amount = form.depth(id);
 
-        let parent = current_node.borrow().get_parent().unwrap();
-        current_node = parent;
-    }
-    // nvim 
+        self.write(format_args!("\n\t{:indent$}", "", indent = amount * 4));
+        self.write(format_args!("\n\t{:indent$}", "", indent = amount * 4));
 
-    amount
-}
+        let node = form.node(id);
 
-impl std::fmt::Debug for Node {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let amount = calculate_nested_amount(&self);
-
-        write!(f, "\n\t{:indent$}", "", indent = amount * 4)?;
-        write!(f, "\n\t{:indent$}", "", indent = amount * 4)?;
-
-        match self {
-            Node::Panel {
-                children,
-                attributes,
-                ..
-            } => {
-                write!(f, "<Panel {:?}> ", attributes)?;
-                for child in children {
-                    child.borrow().fmt(f).unwrap();
-                }
-                write!(f, "\n\t{:indent$}", "", indent = amount * 4)?;
-                writeln!(f, "</Panel>")?;
-            }
-            Node::Rust { code, .. } => {
-                write!(f, "{:?}", code)?;
-            }
-            Node::Border { children, .. } => {
-                write!(f, "<Border>")?;
-                for child in children {
-                    child.borrow().fmt(f).unwrap();
-                }
-                write!(f, "\n\t{:indent$}", "", indent = amount * 4)?;
-                writeln!(f, "</Border>")?;
-            }
-            Node::Grid { children, .. } => {
-                write!(f, "<Grid>")?;
-                for child in children {
-                    child.borrow().fmt(f).unwrap();
-                }
-                write!(f, "\n\t{:indent$}", "", indent = amount * 4)?;
-                writeln!(f, "</Grid>")?;
-            }
-            Node::Default { children, .. } => {
-                write!(f, "<Default>")?;
-                for child in children {
-                    child.borrow().fmt(f).unwrap();
-                }
-                write!(f, "\n\t{:indent$}", "", indent = amount * 4)?;
-                writeln!(f, "</Default>")?;
-            }
-            Node::Strip {
-                children,
-                attributes,
-                ..
-            } => {
-                write!(f, "<Strip {:?}>", attributes)?;
-                for child in children {
-                    child.borrow().fmt(f).unwrap();
-                }
-                write!(f, "\n\t{:indent$}", "", indent = amount * 4)?;
-                writeln!(f, "</Strip>")?;
+        if let Node::Rust { code, .. } = node {
+            self.write(format_args!("{:?}", code));
+            return;
+        }
+
+        match node.get_attributes() {
+            Some(attributes) if !attributes.is_empty() => {
+                self.write(format_args!("<{} {:?}>", node.name(), attributes))
             }
+            _ => self.write(format_args!("<{}>", node.name())),
         }
 
-        Ok(())
+        visit_children(self, form, id);
+
+        self.write(format_args!("\n\t{:indent$}", "", indent = amount * 4));
+        self.write(format_args!("</{}>\n", node.name()));
+    }
+}
+
+impl fmt::Debug for XMLForm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut writer = DebugWriter { f, result: Ok(()) };
+
+        writer.visit_node(self, self.root());
+        writer.result
     }
 }
 
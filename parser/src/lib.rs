@@ -1,5 +1,12 @@
 mod core;
+pub mod element;
 mod err;
 mod fmt;
+mod print;
+pub mod template;
+pub mod visit;
 
 pub use core::*;
+pub use element::{Element, Layout};
+pub use err::{Location, ParseError, Span};
+pub use print::PrintOptions;
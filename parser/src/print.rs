@@ -0,0 +1,372 @@
+use std::borrow::Cow;
+
+use quick_xml::escape::{escape, partial_escape};
+
+use crate::core::{Node, NodeId, XMLForm};
+use crate::visit::{visit_children, Visit};
+
+/// Options for [`XMLForm::to_xml_string`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrintOptions {
+    /// Written once per nesting level in front of every line.
+    pub indent: String,
+    /// Starts the document with `<?xml version="1.0" encoding="utf-8"?>`.
+    pub declaration: bool,
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        PrintOptions {
+            indent: "    ".to_string(),
+            declaration: false,
+        }
+    }
+}
+
+impl XMLForm {
+    /// Prints the form as indented XML.
+    ///
+    /// Attributes are sorted by name and every element and Rust block starts on its own line,
+    /// so two forms that compare equal print the same. Code is re-indented to its depth.
+    /// Parsing the output gives back an equal form, apart from the indentation of the
+    /// continuation lines of code.
+    pub fn to_xml_string(&self, options: &PrintOptions) -> String {
+        let mut printer = XmlPrinter {
+            options,
+            out: String::new(),
+        };
+
+        if options.declaration {
+            printer
+                .out
+                .push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        }
+
+        visit_children(&mut printer, self, self.root());
+
+        printer.out
+    }
+}
+
+struct XmlPrinter<'o> {
+    options: &'o PrintOptions,
+    out: String,
+}
+
+impl Visit<'_> for XmlPrinter<'_> {
+    fn visit_node(&mut self, form: &XMLForm, id: NodeId) {
+        // the implicit root is not printed, its children start at depth 0
+        let indent = self.options.indent.repeat(form.depth(id) - 1);
+
+        let node = form.node(id);
+
+        if let Node::Rust { code, .. } = node {
+            self.out.push_str(&indent);
+
+            // empty code would vanish, and code right after other code would be merged with it
+            if code.is_empty() {
+                self.out.push_str("<Rust/>\n");
+                return;
+            }
+
+            let explicit = form
+                .previous_sibling(id)
+                .is_some_and(|sibling| matches!(form.node(sibling), Node::Rust { .. }));
+
+            if explicit {
+                self.out.push_str("<Rust>");
+            }
+
+            let code = reindent(code, &indent);
+
+            // Rust full of `&` and `<` stays readable inside CDATA, which also keeps the
+            // whitespace at the ends of the code the parser trims from text
+            let padded =
+                code.starts_with(char::is_whitespace) || code.ends_with(char::is_whitespace);
+
+            if (padded || code.contains(['<', '&'])) && !code.contains("]]>") {
+                self.out.push_str(&format!("<![CDATA[{}]]>", code));
+            } else {
+                self.out.push_str(&escape_ends(&partial_escape(&code)));
+            }
+
+            if explicit {
+                self.out.push_str("</Rust>");
+            }
+
+            self.out.push('\n');
+
+            return;
+        }
+
+        self.out.push_str(&indent);
+        self.out.push('<');
+        self.out.push_str(node.name());
+
+        if let Some(attributes) = node.get_attributes() {
+            let mut attributes: Vec<(&str, &str)> = attributes.iter().collect();
+            attributes.sort();
+
+            for (name, value) in attributes {
+                self.out
+                    .push_str(&format!(" {}=\"{}\"", name, escape(value)));
+            }
+        }
+
+        if form.children(id).is_empty() {
+            self.out.push_str("/>\n");
+            return;
+        }
+
+        self.out.push_str(">\n");
+
+        visit_children(self, form, id);
+
+        self.out
+            .push_str(&format!("{}</{}>\n", indent, node.name()));
+    }
+}
+
+/// Indents the continuation lines of `code` by `indent`, keeping their relative indentation.
+fn reindent(code: &str, indent: &str) -> String {
+    let dedented = dedent(code);
+
+    // single lines and code with multi-line literals are kept as they are
+    if let Cow::Borrowed(_) = dedented {
+        return code.to_string();
+    }
+
+    dedented
+        .split('\n')
+        .enumerate()
+        .map(|(i, line)| match line.is_empty() || i == 0 {
+            true => line.to_string(),
+            false => format!("{}{}", indent, line),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Writes whitespace at the ends of `text` as character references, which the parser keeps.
+fn escape_ends(text: &str) -> String {
+    let reference = |c: char| format!("&#{};", c as u32);
+
+    let mut escaped = text.to_string();
+
+    if let Some(last) = text.chars().last().filter(|c| c.is_whitespace()) {
+        escaped.truncate(escaped.len() - last.len_utf8());
+        escaped.push_str(&reference(last));
+    }
+
+    if let Some(first) = text.chars().next().filter(|c| c.is_whitespace()) {
+        escaped.replace_range(..first.len_utf8(), &reference(first));
+    }
+
+    escaped
+}
+
+/// Removes the indentation the continuation lines of `code` share.
+///
+/// The first line is already trimmed by the parser, so what is left is the indentation of the code
+/// relative to its first line. Code with a string literal spanning lines is kept as it is,
+/// its indentation is part of the string.
+pub(crate) fn dedent(code: &str) -> Cow<'_, str> {
+    let Some((first, rest)) = code.split_once('\n') else {
+        return Cow::Borrowed(code);
+    };
+
+    if has_multiline_literal(code) {
+        return Cow::Borrowed(code);
+    }
+
+    let common = rest
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    let mut dedented = first.to_string();
+
+    for line in rest.split('\n') {
+        dedented.push('\n');
+        dedented.push_str(line.get(common..).unwrap_or(line.trim_start()));
+    }
+
+    Cow::Owned(dedented)
+}
+
+/// Whether a literal in `code` contains a line break, code that does not lex counts as one.
+fn has_multiline_literal(code: &str) -> bool {
+    fn visit(stream: proc_macro2::TokenStream) -> bool {
+        stream.into_iter().any(|token| match token {
+            proc_macro2::TokenTree::Group(group) => visit(group.stream()),
+            proc_macro2::TokenTree::Literal(literal) => literal.to_string().contains('\n'),
+            _ => false,
+        })
+    }
+
+    code.parse().map_or(true, visit)
+}
+
+/// Whether `form` and `other` are equal, apart from the indentation of code the printer changes.
+#[cfg(test)]
+fn same_tree(form: &XMLForm, other: &XMLForm) -> bool {
+    let nodes = |form: &XMLForm| {
+        form.descendants(form.root())
+            .map(|id| {
+                let mut node = form.node(id).clone();
+
+                if let Node::Rust { code, .. } = &mut node {
+                    *code = dedent(code).into_owned();
+                }
+
+                (form.depth(id), node)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    nodes(form) == nodes(other)
+}
+
+mod test {
+    #[test]
+    fn test_print() {
+        use super::{same_tree, PrintOptions};
+        use crate::XMLForm;
+
+        let xml = r#"
+        <Strip   gap="@gap"  direction="south" >
+            <Panel value="0.4" size="relative"><Strip direction="east">
+                        <Panel size="exact" value="250.0">
+                            if ui.button("Hi I am a button!").clicked() {
+                                println!("Button clicked!");
+                            }
+                        </Panel>
+                        <Panel size="remainder"></Panel>
+                    </Strip>
+            </Panel>
+        </Strip>
+        "#;
+
+        let form = XMLForm::try_from(xml.to_string()).unwrap();
+
+        let printed = form.to_xml_string(&PrintOptions::default());
+
+        assert_eq!(
+            printed,
+            r#"<Strip direction="south" gap="@gap">
+    <Panel size="relative" value="0.4">
+        <Strip direction="east">
+            <Panel size="exact" value="250.0">
+                if ui.button("Hi I am a button!").clicked() {
+                    println!("Button clicked!");
+                }
+            </Panel>
+            <Panel size="remainder"/>
+        </Strip>
+    </Panel>
+</Strip>
+"#
+        );
+
+        let reparsed = XMLForm::try_from(printed.clone()).unwrap();
+
+        assert_ne!(reparsed, form);
+        assert!(same_tree(&reparsed, &form));
+        assert_eq!(reparsed.to_xml_string(&PrintOptions::default()), printed);
+    }
+
+    #[test]
+    fn test_print_escaping() {
+        use super::PrintOptions;
+        use crate::XMLForm;
+
+        let xml = r#"<Panel label="a &lt; b &amp;&amp; &quot;c&quot;">
+            <![CDATA[if a < b && c { ui.label("<"); }]]>
+        </Panel>"#;
+
+        let form = XMLForm::try_from(xml.to_string()).unwrap();
+        let printed = form.to_xml_string(&PrintOptions::default());
+
+        assert_eq!(
+            printed,
+            r#"<Panel label="a &lt; b &amp;&amp; &quot;c&quot;">
+    <![CDATA[if a < b && c { ui.label("<"); }]]>
+</Panel>
+"#
+        );
+        assert_eq!(XMLForm::try_from(printed).unwrap(), form);
+    }
+
+    #[test]
+    fn test_print_round_trip() {
+        use super::{same_tree, PrintOptions};
+        use crate::core::Node;
+        use crate::err::{Location, Span};
+        use crate::{Attributes, XMLForm};
+
+        let round_trip = |xml: &str, expected: &str| {
+            let form = XMLForm::try_from(xml.to_string()).unwrap();
+            let printed = form.to_xml_string(&PrintOptions::default());
+
+            assert_eq!(printed, expected);
+
+            let reparsed = XMLForm::try_from(printed.clone()).unwrap();
+
+            assert!(same_tree(&reparsed, &form));
+            assert_eq!(reparsed.len(), form.len());
+            assert_eq!(reparsed.to_xml_string(&PrintOptions::default()), printed);
+        };
+
+        round_trip("<Panel><Rust/></Panel>", "<Panel>\n    <Rust/>\n</Panel>\n");
+
+        round_trip(
+            "<Panel>a();<Rust>b();</Rust><![CDATA[c(&d);]]></Panel>",
+            "<Panel>\n    a();\n    <Rust>b();</Rust>\n    <Rust><![CDATA[c(&d);]]></Rust>\n</Panel>\n",
+        );
+
+        round_trip(
+            "<Panel><Panel>if a {\n  b();\n}</Panel></Panel>",
+            "<Panel>\n    <Panel>\n        if a {\n          b();\n        }\n    </Panel>\n</Panel>\n",
+        );
+
+        // the indentation inside a string literal is kept
+        round_trip(
+            "<Panel>ui.label(\"a\n  b\");</Panel>",
+            "<Panel>\n    ui.label(\"a\n  b\");\n</Panel>\n",
+        );
+
+        // the parser trims text, but not CDATA
+        round_trip(
+            "<Panel><![CDATA[  a(); ]]></Panel>",
+            "<Panel>\n    <![CDATA[  a(); ]]>\n</Panel>\n",
+        );
+
+        // code that cannot be put in CDATA keeps its ends as character references
+        let mut form = XMLForm::new();
+        let panel = form.append(
+            form.root(),
+            Node::Panel {
+                attributes: Attributes::from_iter([("size", "remainder")]),
+            },
+        );
+
+        form.append(
+            panel,
+            Node::Rust {
+                code: " a(\"]]>\");\t".to_string(),
+                location: Location::default(),
+                span: Span::default(),
+            },
+        );
+
+        let printed = form.to_xml_string(&PrintOptions::default());
+
+        assert_eq!(
+            printed,
+            "<Panel size=\"remainder\">\n    &#32;a(\"]]&gt;\");&#9;\n</Panel>\n"
+        );
+        assert_eq!(XMLForm::try_from(printed).unwrap(), form);
+    }
+}
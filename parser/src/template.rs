@@ -0,0 +1,390 @@
+//! `<Template>` definitions and the `<Use>` elements expanding them.
+
+use std::collections::HashMap;
+
+use crate::attribute::parse_string;
+use crate::core::{Attributes, Node, NodeId, XMLForm};
+use crate::err::{Location, ParseError};
+use crate::visit::{visit_children_mut, VisitMut};
+
+struct Template {
+    params: Vec<String>,
+    /// The content of the `<Template>`, as the top-level nodes of a form.
+    body: XMLForm,
+}
+
+/// Replaces every `<Use template="..." .../>` by the body of the `<Template>` it names.
+///
+/// `<Template name="..." params="a, b">` may be defined anywhere in the form but inside another
+/// template, and is taken out of the tree. Inside the body `$a` stands for the value the `<Use>`
+/// passes as `a`. An attribute that is just `$a` takes the value as it is, anywhere else the `@`
+/// of a Rust value is dropped, so both `value="@$a * 2.0"` and `ui.add_space($a);` work for
+/// `a="@gap"` as well as `a="4.0"`.
+/// `$$` stands for a plain `$`.
+pub fn expand_templates(form: &mut XMLForm) -> Result<(), ParseError> {
+    let templates = collect_templates(form)?;
+
+    let mut uses = Uses {
+        templates: &templates,
+        stack: Vec::new(),
+        error: None,
+    };
+
+    let root = form.root();
+    uses.visit_node_mut(form, root);
+
+    match uses.error {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+fn collect_templates(form: &mut XMLForm) -> Result<HashMap<String, Template>, ParseError> {
+    let definitions: Vec<NodeId> = form
+        .descendants(form.root())
+        .filter(|&id| form.node(id).name() == "Template")
+        .collect();
+
+    let mut templates = HashMap::new();
+
+    for &id in &definitions {
+        let attributes = attributes(form.node(id));
+
+        // templates are defined at one level, a body may only use other templates
+        if form
+            .ancestors(id)
+            .any(|ancestor| form.node(ancestor).name() == "Template")
+        {
+            return Err(ParseError::UnexpectedElement {
+                element: "Template".to_string(),
+                parent: "Template".to_string(),
+                location: attributes.location(),
+            });
+        }
+
+        attributes.check(|name| name == "name" || name == "params")?;
+
+        let name = parse_string(attributes, "name")?;
+        let params = attributes
+            .get("params")
+            .map(|params| {
+                params
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|param| !param.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut body = XMLForm::new();
+        let root = body.root();
+
+        for &child in form.children(id) {
+            body.append_subtree(root, form, child);
+        }
+
+        if templates.contains_key(&name) {
+            return Err(ParseError::DuplicateTemplate {
+                template: name,
+                location: attributes.location(),
+            });
+        }
+
+        templates.insert(name, Template { params, body });
+    }
+
+    for id in definitions {
+        form.detach(id);
+    }
+
+    Ok(templates)
+}
+
+struct Uses<'t> {
+    templates: &'t HashMap<String, Template>,
+    /// The templates being expanded, outermost first.
+    stack: Vec<&'t str>,
+    error: Option<ParseError>,
+}
+
+impl VisitMut for Uses<'_> {
+    fn visit_element_mut(&mut self, form: &mut XMLForm, id: NodeId) {
+        if self.error.is_some() {
+            return;
+        }
+
+        if form.node(id).name() != "Use" {
+            visit_children_mut(self, form, id);
+            return;
+        }
+
+        match self.instantiate(attributes(form.node(id))) {
+            // the uses inside the body were expanded already
+            Ok(fragment) if self.error.is_none() => {
+                form.splice(id, &fragment);
+            }
+            Ok(_) => (),
+            Err(error) => self.error = Some(error),
+        }
+    }
+}
+
+impl<'t> Uses<'t> {
+    fn instantiate(&mut self, attributes: &Attributes) -> Result<XMLForm, ParseError> {
+        let name = parse_string(attributes, "template")?;
+
+        let Some((name, template)) = self.templates.get_key_value(&name) else {
+            return Err(ParseError::UnknownTemplate {
+                template: name,
+                location: attributes.location_of("template"),
+            });
+        };
+
+        if self.stack.contains(&name.as_str()) {
+            return Err(ParseError::RecursiveTemplate {
+                template: name.clone(),
+                location: attributes.location(),
+            });
+        }
+
+        // every parameter has to be passed, and nothing else
+        attributes.check(|attribute| {
+            attribute == "template" || template.params.iter().any(|param| param == attribute)
+        })?;
+
+        let mut values = HashMap::new();
+
+        for param in &template.params {
+            values.insert(param.as_str(), parse_string(attributes, param)?);
+        }
+
+        let mut fragment = XMLForm::new();
+        let root = fragment.root();
+
+        for &child in template.body.children(template.body.root()) {
+            copy_subtree(&mut fragment, root, &template.body, child, name, &values)?;
+        }
+
+        self.stack.push(name);
+
+        let root = fragment.root();
+        self.visit_node_mut(&mut fragment, root);
+
+        self.stack.pop();
+
+        Ok(fragment)
+    }
+}
+
+fn copy_subtree(
+    form: &mut XMLForm,
+    parent: NodeId,
+    body: &XMLForm,
+    id: NodeId,
+    template: &str,
+    values: &HashMap<&str, String>,
+) -> Result<(), ParseError> {
+    let mut node = body.node(id).clone();
+
+    let unknown_parameter = |parameter: String, location: Location| ParseError::UnknownParameter {
+        template: template.to_string(),
+        parameter,
+        location,
+    };
+
+    if let Node::Rust { code, location, .. } = &mut node {
+        *code =
+            substitute(code, values, false).map_err(|name| unknown_parameter(name, *location))?;
+    }
+
+    if let Some(attributes) = node.get_attributes_mut() {
+        let locations: HashMap<String, Location> = attributes
+            .iter()
+            .map(|(name, _)| (name.to_string(), attributes.location_of(name)))
+            .collect();
+
+        for (name, value) in attributes.iter_mut() {
+            *value = substitute(value, values, true)
+                .map_err(|parameter| unknown_parameter(parameter, locations[name]))?;
+        }
+    }
+
+    let copy = form.append(parent, node);
+
+    for &child in body.children(id) {
+        copy_subtree(form, copy, body, child, template, values)?;
+    }
+
+    Ok(())
+}
+
+/// Replaces the `$name`s in `text`, or returns the first name without a value.
+///
+/// `attribute` is set for attribute values, where a lone `$name` keeps the `@` of the value.
+fn substitute(
+    text: &str,
+    values: &HashMap<&str, String>,
+    attribute: bool,
+) -> Result<String, String> {
+    if let Some(value) = text
+        .strip_prefix('$')
+        .filter(|_| attribute)
+        .and_then(|name| values.get(name))
+    {
+        return Ok(value.clone());
+    }
+
+    let mut substituted = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('$') {
+        substituted.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+
+        if let Some(after) = rest.strip_prefix('$') {
+            substituted.push('$');
+            rest = after;
+            continue;
+        }
+
+        // inline layouts are written as tokens, there `$a` may come out as `$ a`
+        let name_start = rest.trim_start();
+        let length = name_start
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(name_start.len());
+        let name = &name_start[..length];
+
+        match values.get(name) {
+            Some(value) => substituted.push_str(value.strip_prefix('@').unwrap_or(value)),
+            None => return Err(name.to_string()),
+        }
+
+        rest = &name_start[length..];
+    }
+
+    substituted.push_str(rest);
+
+    Ok(substituted)
+}
+
+fn attributes(node: &Node) -> &Attributes {
+    node.get_attributes().expect("elements have attributes")
+}
+
+mod test {
+    #[test]
+    fn test_templates() {
+        use super::expand_templates;
+        use crate::{PrintOptions, XMLForm};
+
+        let xml = r#"
+        <Template name="Quad" params="size, gap">
+            <Strip direction="north" size="$size" gap="$gap">
+                <Panel size="exact" value="@$gap * 2.0">ui.add_space($gap);</Panel>
+                <Use template="Label" text="&quot;$$5&quot;"/>
+            </Strip>
+        </Template>
+        <Template name="Label" params="text">
+            <Panel size="remainder">ui.label($text);</Panel>
+        </Template>
+        <Strip direction="west">
+            <Use template="Quad" size="remainder" gap="@self.gap"/>
+            <Use template="Quad" size="remainder" gap="4.0"/>
+        </Strip>
+        "#;
+
+        let mut form = XMLForm::try_from(xml.to_string()).unwrap();
+        expand_templates(&mut form).unwrap();
+
+        let quad = |gap: &str, doubled: &str| {
+            format!(
+                r#"    <Strip direction="north" gap="{gap}" size="remainder">
+        <Panel size="exact" value="@{doubled} * 2.0">
+            ui.add_space({doubled});
+        </Panel>
+        <Panel size="remainder">
+            ui.label("$5");
+        </Panel>
+    </Strip>
+"#
+            )
+        };
+
+        assert_eq!(
+            form.to_xml_string(&PrintOptions::default()),
+            format!(
+                "<Strip direction=\"west\">\n{}{}</Strip>\n",
+                quad("@self.gap", "self.gap"),
+                quad("4.0", "4.0")
+            )
+        );
+    }
+
+    #[test]
+    fn test_template_errors() {
+        use super::expand_templates;
+        use crate::err::{Location, ParseError};
+        use crate::XMLForm;
+
+        let expand = |xml: &str| {
+            let mut form = XMLForm::try_from(xml.to_string()).unwrap();
+            expand_templates(&mut form).err()
+        };
+
+        let template =
+            "<Template name=\"T\" params=\"a\"><Panel>ui.label($a, $b);</Panel></Template>\n";
+
+        assert_eq!(
+            expand(&format!("{}<Use template=\"T\"/>", template)),
+            Some(ParseError::MissingAttribute {
+                element: "Use".to_string(),
+                attribute: "a".to_string(),
+                location: Location::new(2, 1),
+            })
+        );
+        assert_eq!(
+            expand(&format!(
+                "{}<Use template=\"T\" a=\"1\" c=\"2\"/>",
+                template
+            )),
+            Some(ParseError::UnknownAttribute {
+                element: "Use".to_string(),
+                attribute: "c".to_string(),
+                location: Location::new(2, 25),
+            })
+        );
+        assert_eq!(
+            expand(&format!("{}<Use template=\"T\" a=\"1\"/>", template)),
+            Some(ParseError::UnknownParameter {
+                template: "T".to_string(),
+                parameter: "b".to_string(),
+                location: Location::new(1, 38),
+            })
+        );
+        assert_eq!(
+            expand("<Use template=\"Nope\"/>"),
+            Some(ParseError::UnknownTemplate {
+                template: "Nope".to_string(),
+                location: Location::new(1, 6),
+            })
+        );
+        assert_eq!(
+            expand("<Template name=\"T\"><Use template=\"T\"/></Template><Use template=\"T\"/>"),
+            Some(ParseError::RecursiveTemplate {
+                template: "T".to_string(),
+                location: Location::new(1, 20),
+            })
+        );
+        assert_eq!(
+            expand(
+                "<Template name=\"T\">\n    <Strip direction=\"east\">\n        <Template name=\"U\"/>\n    </Strip>\n</Template>"
+            ),
+            Some(ParseError::UnexpectedElement {
+                element: "Template".to_string(),
+                parent: "Template".to_string(),
+                location: Location::new(3, 9),
+            })
+        );
+    }
+}
@@ -0,0 +1,217 @@
+//! Walking the node tree of an [`XMLForm`].
+//!
+//! [`Visit`] and [`VisitMut`] recurse into every child by default. A pass overrides the hooks of
+//! the elements it cares about and calls [`visit_children`] (or [`visit_children_mut`]) from
+//! them to keep descending, or leaves it out to skip the subtree.
+//!
+//! Code generation in `egui_xml_macros` is not a pass, it matches on the typed
+//! [`Element`](crate::Element) model built from the form once all passes have run.
+
+use crate::core::{Node, NodeId, XMLForm};
+
+/// Read-only walk over an [`XMLForm`].
+pub trait Visit<'a> {
+    /// Dispatches to the hook of the element at `id`.
+    fn visit_node(&mut self, form: &'a XMLForm, id: NodeId) {
+        visit_node(self, form, id);
+    }
+
+    fn visit_default(&mut self, form: &'a XMLForm, id: NodeId) {
+        visit_children(self, form, id);
+    }
+
+    fn visit_strip(&mut self, form: &'a XMLForm, id: NodeId) {
+        visit_children(self, form, id);
+    }
+
+    fn visit_panel(&mut self, form: &'a XMLForm, id: NodeId) {
+        visit_children(self, form, id);
+    }
+
+    fn visit_grid(&mut self, form: &'a XMLForm, id: NodeId) {
+        visit_children(self, form, id);
+    }
+
+    fn visit_border(&mut self, form: &'a XMLForm, id: NodeId) {
+        visit_children(self, form, id);
+    }
+
+    /// An element the parser does not know, see [`Node::Element`].
+    fn visit_element(&mut self, form: &'a XMLForm, id: NodeId) {
+        visit_children(self, form, id);
+    }
+
+    /// Rust nodes have no children.
+    fn visit_rust(&mut self, _form: &'a XMLForm, _id: NodeId) {}
+}
+
+pub fn visit_node<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, form: &'a XMLForm, id: NodeId) {
+    match form.node(id) {
+        Node::Default { .. } => visitor.visit_default(form, id),
+        Node::Strip { .. } => visitor.visit_strip(form, id),
+        Node::Panel { .. } => visitor.visit_panel(form, id),
+        Node::Grid { .. } => visitor.visit_grid(form, id),
+        Node::Border { .. } => visitor.visit_border(form, id),
+        Node::Element { .. } => visitor.visit_element(form, id),
+        Node::Rust { .. } => visitor.visit_rust(form, id),
+    }
+}
+
+pub fn visit_children<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, form: &'a XMLForm, id: NodeId) {
+    for &child in form.children(id) {
+        visitor.visit_node(form, child);
+    }
+}
+
+/// Walk over an [`XMLForm`] that may change nodes and the tree while descending.
+pub trait VisitMut {
+    /// Dispatches to the hook of the element at `id`.
+    fn visit_node_mut(&mut self, form: &mut XMLForm, id: NodeId) {
+        visit_node_mut(self, form, id);
+    }
+
+    fn visit_default_mut(&mut self, form: &mut XMLForm, id: NodeId) {
+        visit_children_mut(self, form, id);
+    }
+
+    fn visit_strip_mut(&mut self, form: &mut XMLForm, id: NodeId) {
+        visit_children_mut(self, form, id);
+    }
+
+    fn visit_panel_mut(&mut self, form: &mut XMLForm, id: NodeId) {
+        visit_children_mut(self, form, id);
+    }
+
+    fn visit_grid_mut(&mut self, form: &mut XMLForm, id: NodeId) {
+        visit_children_mut(self, form, id);
+    }
+
+    fn visit_border_mut(&mut self, form: &mut XMLForm, id: NodeId) {
+        visit_children_mut(self, form, id);
+    }
+
+    /// An element the parser does not know, see [`Node::Element`].
+    fn visit_element_mut(&mut self, form: &mut XMLForm, id: NodeId) {
+        visit_children_mut(self, form, id);
+    }
+
+    /// Rust nodes have no children.
+    fn visit_rust_mut(&mut self, _form: &mut XMLForm, _id: NodeId) {}
+}
+
+pub fn visit_node_mut<V: VisitMut + ?Sized>(visitor: &mut V, form: &mut XMLForm, id: NodeId) {
+    match form.node(id) {
+        Node::Default { .. } => visitor.visit_default_mut(form, id),
+        Node::Strip { .. } => visitor.visit_strip_mut(form, id),
+        Node::Panel { .. } => visitor.visit_panel_mut(form, id),
+        Node::Grid { .. } => visitor.visit_grid_mut(form, id),
+        Node::Border { .. } => visitor.visit_border_mut(form, id),
+        Node::Element { .. } => visitor.visit_element_mut(form, id),
+        Node::Rust { .. } => visitor.visit_rust_mut(form, id),
+    }
+}
+
+/// Visits the children of `id` in order.
+///
+/// The child list is read again after every child, so a hook may append to or rewrite the
+/// children that come after the one it is visiting.
+pub fn visit_children_mut<V: VisitMut + ?Sized>(visitor: &mut V, form: &mut XMLForm, id: NodeId) {
+    let mut index = 0;
+
+    while let Some(&child) = form.children(id).get(index) {
+        visitor.visit_node_mut(form, child);
+        index += 1;
+    }
+}
+
+mod test {
+    #[test]
+    fn test_visit() {
+        use super::{visit_children, visit_children_mut, Visit, VisitMut};
+        use crate::core::{Node, NodeId, XMLForm};
+
+        let xml = r#"
+        <Strip direction="north">
+            <Panel size="remainder">ui.label("a");</Panel>
+            <Strip direction="west" size="remainder">
+                <Panel size="exact" value="10">ui.label("b");</Panel>
+            </Strip>
+        </Strip>
+        "#;
+
+        let mut form = XMLForm::try_from(xml.to_string()).unwrap();
+
+        // collects the code of every Rust node, but does not look into nested strips
+        struct Shallow<'a> {
+            strips: usize,
+            code: Vec<&'a str>,
+        }
+
+        impl<'a> Visit<'a> for Shallow<'a> {
+            fn visit_strip(&mut self, form: &'a XMLForm, id: NodeId) {
+                self.strips += 1;
+
+                if self.strips == 1 {
+                    visit_children(self, form, id);
+                }
+            }
+
+            fn visit_rust(&mut self, form: &'a XMLForm, id: NodeId) {
+                if let Node::Rust { code, .. } = form.node(id) {
+                    self.code.push(code);
+                }
+            }
+        }
+
+        let mut shallow = Shallow {
+            strips: 0,
+            code: Vec::new(),
+        };
+        shallow.visit_node(&form, form.root());
+
+        assert_eq!(shallow.strips, 2);
+        assert_eq!(shallow.code, vec!["ui.label(\"a\");"]);
+
+        // gives every panel without code a Rust node
+        struct FillPanels;
+
+        impl VisitMut for FillPanels {
+            fn visit_strip_mut(&mut self, form: &mut XMLForm, id: NodeId) {
+                let panel = form.append(
+                    id,
+                    Node::Panel {
+                        attributes: [("size", "remainder")].into_iter().collect(),
+                    },
+                );
+
+                assert!(form.children(panel).is_empty());
+
+                visit_children_mut(self, form, id);
+            }
+
+            fn visit_panel_mut(&mut self, form: &mut XMLForm, id: NodeId) {
+                if form.children(id).is_empty() {
+                    let rust = Node::Rust {
+                        code: "ui.label(\"filled\");".to_string(),
+                        location: Default::default(),
+                        span: Default::default(),
+                    };
+
+                    form.append(id, rust);
+                }
+            }
+        }
+
+        let root = form.root();
+        FillPanels.visit_node_mut(&mut form, root);
+
+        let filled = form
+            .descendants(form.root())
+            .filter(|&id| {
+                matches!(form.node(id), Node::Rust { code, .. } if code == "ui.label(\"filled\");")
+            })
+            .count();
+
+        assert_eq!(filled, 2);
+    }
+}
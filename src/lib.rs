@@ -6,14 +6,14 @@
 //!
 //! ```rust
 //! use eframe::egui;
-//! use egui::{Rounding, Ui};
+//! use egui::{CornerRadius, Ui};
 //! use egui_xml::load_layout;
 //!
 //! struct MyApp;
 //!
 //! fn color_background(ui: &mut Ui, color: egui::Color32) {
 //!     ui.painter()
-//!         .rect_filled(ui.available_rect_before_wrap(), Rounding::same(5.0), color);
+//!         .rect_filled(ui.available_rect_before_wrap(), CornerRadius::same(5), color);
 //! }
 //!
 //! impl eframe::App for MyApp {
@@ -43,3 +43,24 @@
 
 pub use egui_xml_macros::load_layout;
 pub use egui_xml_macros::load_layout_file;
+
+/// The column a `<DataGrid>` is sorted by, bound with its `sort` attribute.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SortState {
+    /// Index of the column, `None` keeps the order of the rows.
+    pub column: Option<usize>,
+    pub descending: bool,
+}
+
+impl SortState {
+    /// What clicking the header of `column` does: sorts by it, or flips the order if it
+    /// already is.
+    pub fn toggle(&mut self, column: usize) {
+        if self.column == Some(column) {
+            self.descending = !self.descending;
+        } else {
+            self.column = Some(column);
+            self.descending = false;
+        }
+    }
+}